@@ -1,9 +1,20 @@
-use std::process::Command;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    process::Command,
+    time::Duration,
+};
+
+#[cfg(target_family = "unix")]
+use {crate::utilities::utils::mount_directory, std::fs};
 
 use eframe::egui;
 use egui::ViewportCommand;
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
-use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
 use tray_item::{IconSource, TrayItem};
 
 #[cfg(target_os = "windows")]
@@ -15,16 +26,30 @@ use tray_icon::menu::MenuEvent;
 use crate::{
     backend::{
         app_config::AppConfig,
+        bundle::Bundle,
         mounting::MountingStorage,
+        providers::ProviderCatalog,
         rclone::{Rclone, Storage},
+        remote_config::ConfigStep,
+        trash::Trash,
     },
     ui::{
-        manage::render_manage, mount_unmount::render_mount_unmount, settings::render_settings,
+        bundle::BundleState,
+        create_wizard::{render_create_wizard, CreateWizard},
+        drive_options::DriveOptions,
+        manage::render_manage,
+        mount_unmount::render_mount_unmount,
+        option_editor::OptionEditor,
+        settings::render_settings,
         top_panel::render_top_panel,
+        unlock_config::render_unlock_config,
     },
     utilities::{
-        enums::{Message, Tab},
-        utils::rclone_config_path,
+        enums::{ConfigFileSource, Message, Tab, TestFailure, TokenState},
+        utils::{
+            detect_rclone_config_file, rclone_config_file, remote_path, remove_vfs_cache,
+            set_rclone_config_file, validate_remote_name,
+        },
     },
 };
 
@@ -32,11 +57,39 @@ use crate::{
 pub struct DriveFUSE {
     pub app_config: AppConfig,
     pub rclone: Rclone,
+    pub providers: ProviderCatalog,
     pub mounted_storages: MountingStorage,
 
     pub current_tab: Tab,
 
     pub new_storage_name: String,
+    pub new_storage_type: Option<String>,
+    pub create_wizard: Option<CreateWizard>,
+
+    pub selected_storage: Option<String>,
+    pub edit_storage_name: String,
+    pub edit_storage_error: Option<String>,
+    pub edit_mount_root: String,
+    pub duplicate_storage_name: String,
+    /// Storage waiting for the user to confirm its deletion.
+    pub delete_storage: Option<String>,
+    pub trash: Trash,
+    pub trash_error: Option<String>,
+    pub option_editor: OptionEditor,
+    pub drive_options: DriveOptions,
+    pub bundle: BundleState,
+
+    pub token_failures: HashMap<String, String>,
+    pub reconnecting: HashSet<String>,
+    /// Latency or error of the last test of each storage.
+    pub storage_tests: HashMap<String, Result<Duration, String>>,
+    pub testing: HashSet<String>,
+
+    pub config_password: String,
+    pub config_password_error: Option<String>,
+
+    pub config_file_source: ConfigFileSource,
+    config_watcher: Option<JoinHandle<()>>,
 
     is_first_run: bool,
     is_close_requested: bool,
@@ -52,7 +105,18 @@ impl DriveFUSE {
         let (tx_egui, rx_egui) = mpsc::unbounded_channel();
 
         let app_config = AppConfig::init();
+
+        let (config_file, config_file_source) =
+            detect_rclone_config_file(app_config.rclone_config_file.clone());
+        tracing::info!(
+            "Using rclone config {} ({})",
+            config_file.display(),
+            config_file_source.name()
+        );
+        set_rclone_config_file(config_file);
+
         let rclone = Rclone::init();
+        let providers = ProviderCatalog::load();
 
         let platform = if cfg!(target_os = "linux") {
             let cmd = "loginctl show-session $(awk '/tty/ {print $1}' <(loginctl)) -p Type | awk -F= '{print $2}'";
@@ -75,14 +139,37 @@ impl DriveFUSE {
         Self {
             app_config,
             rclone,
+            providers,
             mounted_storages: MountingStorage::default(),
 
             current_tab: Tab::MountUnmount,
 
-            // selected_storage: None,
+            selected_storage: None,
             new_storage_name: String::new(),
+            new_storage_type: None,
+            create_wizard: None,
             // new_storage_drive_letter: String::from("N/A"),
-            // edit_storage_name: String::new(),
+            edit_storage_name: String::new(),
+            edit_storage_error: None,
+            edit_mount_root: String::new(),
+            duplicate_storage_name: String::new(),
+            delete_storage: None,
+            trash: Trash::load(),
+            trash_error: None,
+            option_editor: OptionEditor::default(),
+            drive_options: DriveOptions::default(),
+            bundle: BundleState::default(),
+
+            token_failures: HashMap::new(),
+            reconnecting: HashSet::new(),
+            storage_tests: HashMap::new(),
+            testing: HashSet::new(),
+            config_password: String::new(),
+            config_password_error: None,
+
+            config_file_source,
+            config_watcher: None,
+
             is_first_run: true,
             is_close_requested: false,
 
@@ -92,6 +179,382 @@ impl DriveFUSE {
             platform,
         }
     }
+
+    /// Checks the name of a new or renamed storage before rclone is started with it.
+    pub fn validate_storage_name(&self, name: &str) -> Result<(), String> {
+        validate_remote_name(name)?;
+
+        if self.rclone.storages.iter().any(|s| s.name == name) {
+            return Err(format!("A storage named {} already exists", name));
+        }
+
+        // rclone refuses to mount over files, and a dead mount can't even be listed
+        #[cfg(target_family = "unix")]
+        {
+            let dir = mount_directory(name);
+            let is_free = match fs::read_dir(&dir) {
+                Ok(mut entries) => entries.next().is_none(),
+                Err(err) => err.kind() == std::io::ErrorKind::NotFound,
+            };
+            if !is_free {
+                return Err(format!("{} is already in use", dir.display()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The storage's options as rclone reported them, secrets still obscured.
+    fn storage_options(&self, name: &str) -> Result<BTreeMap<String, String>, String> {
+        self.rclone
+            .storages
+            .iter()
+            .find(|storage| storage.name == name)
+            .map(|storage| storage.options.clone())
+            .ok_or_else(|| format!("{} is not in the rclone config", name))
+    }
+
+    /// Unmounts the storage before its section changes, refusing mounts DriveFUSE can't stop.
+    /// Our own mounts are checked first, on Unix an empty remote looks like it isn't mounted.
+    pub fn unmount_for_edit(&mut self, name: &str) -> Result<(), String> {
+        // their results come back under the current name
+        if self.testing.contains(name) || self.reconnecting.contains(name) {
+            return Err(format!(
+                "Wait for the test or reconnect of {} to finish",
+                name
+            ));
+        }
+
+        if self.mounted_storages.is_tracked(name) {
+            self.mounted_storages.unmount(name.to_owned());
+            if self.mounted_storages.is_tracked(name) {
+                return Err(format!("Failed to unmount {}", name));
+            }
+        } else if self.mounted_storages.is_mounted(name.to_owned()) {
+            return Err(format!(
+                "{} is mounted outside of DriveFUSE, unmount it first",
+                name
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Renames a remote together with everything DriveFUSE keeps under its name. A mounted
+    /// remote is unmounted first since its rclone process still uses the old name.
+    pub fn rename_storage(&mut self, old_name: &str, new_name: &str) -> Result<(), String> {
+        self.validate_storage_name(new_name)?;
+        self.unmount_for_edit(old_name)?;
+
+        if self.rclone.is_encrypted {
+            // the file can't be edited, so rclone recreates the remote under the new name
+            Rclone::create_remote(new_name, &self.storage_options(old_name)?)?;
+            if let Err(err) = Rclone::delete_remote(old_name) {
+                if let Err(undo_err) = Rclone::delete_remote(new_name) {
+                    tracing::error!("Error removing storage {}: {}", new_name, undo_err);
+                }
+                return Err(err);
+            }
+        } else {
+            Rclone::edit_config(|document| document.rename_section(old_name, new_name))?;
+        }
+
+        self.app_config.rename_drive(old_name, new_name);
+        self.mounted_storages.rename(old_name, new_name);
+        if let Some(failure) = self.token_failures.remove(old_name) {
+            self.token_failures.insert(new_name.to_owned(), failure);
+        }
+        if let Some(result) = self.storage_tests.remove(old_name) {
+            self.storage_tests.insert(new_name.to_owned(), result);
+        }
+
+        #[cfg(target_family = "unix")]
+        {
+            let old_dir = mount_directory(old_name);
+            let new_dir = mount_directory(new_name);
+            if old_dir.exists() && !new_dir.exists() {
+                if let Err(err) = fs::rename(&old_dir, &new_dir) {
+                    tracing::error!(
+                        "Error moving {} to {}: {}",
+                        old_dir.display(),
+                        new_dir.display(),
+                        err
+                    );
+                }
+            }
+        }
+
+        if let Some(storage) = self.rclone.storages.iter_mut().find(|s| s.name == old_name) {
+            new_name.clone_into(&mut storage.name);
+        }
+
+        tracing::info!("Renamed storage {} to {}", old_name, new_name);
+
+        Ok(())
+    }
+
+    /// Unmounts and deletes the storage, keeping its section and settings in the trash.
+    pub fn delete_storage(&mut self, name: &str) -> Result<(), String> {
+        self.unmount_for_edit(name)?;
+
+        // an encrypted config's secrets must not end up in the plaintext trash
+        let use_trash = !self.rclone.is_encrypted;
+        if use_trash {
+            let names = HashSet::from([name.to_owned()]);
+            let remote =
+                Bundle::export(&self.rclone.storages, &names, true, Some(&self.app_config))
+                    .remotes
+                    .pop()
+                    .ok_or_else(|| format!("{} is not in the rclone config", name))?;
+            self.trash.push(remote)?;
+        }
+
+        if let Err(err) = Rclone::delete_remote(name) {
+            // the storage is still there, so it shouldn't be restorable as well
+            if use_trash {
+                if let Err(trash_err) = self.trash.pop() {
+                    tracing::error!("Error updating trash: {}", trash_err);
+                }
+            }
+            return Err(err);
+        }
+
+        self.app_config.remove_drive(name);
+        self.token_failures.remove(name);
+        self.storage_tests.remove(name);
+        self.rclone.storages.retain(|storage| storage.name != name);
+        if self.selected_storage.as_deref() == Some(name) {
+            self.selected_storage = None;
+        }
+
+        #[cfg(target_family = "unix")]
+        {
+            // remove_dir leaves anything that is still in there alone
+            let dir = mount_directory(name);
+            if dir.exists() {
+                if let Err(err) = fs::remove_dir(&dir) {
+                    tracing::error!("Error removing {}: {}", dir.display(), err);
+                }
+            }
+        }
+        remove_vfs_cache(name);
+
+        tracing::info!("Deleted storage {}", name);
+
+        Ok(())
+    }
+
+    /// Creates a deleted storage again from its trash entry.
+    pub fn restore_storage(&mut self, index: usize) -> Result<(), String> {
+        let remote = match self.trash.entries.get(index) {
+            Some(entry) => entry.remote.clone(),
+            None => return Err("The storage is no longer in the trash".to_owned()),
+        };
+        self.validate_storage_name(&remote.name)?;
+
+        Rclone::create_remote(&remote.name, &remote.options)?;
+        if let Some(settings) = &remote.settings {
+            self.app_config.apply_settings(&remote.name, settings);
+        }
+        self.trash.remove(index)?;
+
+        self.rclone = Rclone::init();
+        tracing::info!("Restored storage {}", remote.name);
+
+        Ok(())
+    }
+
+    /// Copies the storage's section, token included, so another shared drive or bucket of
+    /// the same account doesn't need a new sign in.
+    pub fn duplicate_storage(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        self.validate_storage_name(new_name)?;
+
+        if self.rclone.is_encrypted {
+            // config dump keeps secrets obscured, so they can go back to rclone as they are
+            Rclone::create_remote(new_name, &self.storage_options(name)?)?;
+        } else {
+            Rclone::edit_config(|document| document.copy_section(name, new_name))?;
+        }
+        tracing::info!("Duplicated storage {} as {}", name, new_name);
+
+        self.rclone = Rclone::init();
+        self.selected_storage = Some(new_name.to_owned());
+        new_name.clone_into(&mut self.edit_storage_name);
+        self.edit_mount_root.clear();
+        self.duplicate_storage_name.clear();
+
+        Ok(())
+    }
+
+    /// A failed reconnect or a test rejected by the provider beats what the token says.
+    /// OAuth storages without a token, like ones imported without secrets, still need one.
+    pub fn token_state(&self, storage: &Storage) -> Option<TokenState> {
+        let token = match &storage.token {
+            Some(token) => token,
+            None if self.needs_sign_in(storage) => return Some(TokenState::Missing),
+            None => return None,
+        };
+        if self.token_failure(&storage.name).is_some() {
+            return Some(TokenState::RefreshFailed);
+        }
+        Some(token.state())
+    }
+
+    fn needs_sign_in(&self, storage: &Storage) -> bool {
+        // a service account signs in without OAuth
+        let has_service_account = storage
+            .options
+            .iter()
+            .any(|(key, value)| key.starts_with("service_account") && !value.is_empty());
+
+        !has_service_account
+            && self
+                .providers
+                .get(&storage.drive_type)
+                .is_some_and(|provider| provider.uses_oauth())
+    }
+
+    pub fn token_failure(&self, name: &str) -> Option<&String> {
+        self.token_failures
+            .get(name)
+            .or_else(|| match self.storage_tests.get(name) {
+                Some(Err(err)) if TestFailure::from_error(err) == TestFailure::Auth => Some(err),
+                _ => None,
+            })
+    }
+
+    /// Closes the creation wizard once rclone saved the remote and selects it for editing.
+    pub fn finish_create_wizard(&mut self) {
+        let wizard = match self.create_wizard.take() {
+            Some(wizard) => wizard,
+            None => return,
+        };
+
+        if let Some(root) = &wizard.mount_root {
+            self.app_config
+                .set_mount_root(wizard.name.clone(), root.clone());
+        }
+        self.edit_mount_root = wizard.mount_root.unwrap_or_default();
+        self.selected_storage = Some(wizard.name.clone());
+        self.edit_storage_name = wizard.name;
+        self.rclone = Rclone::init();
+    }
+
+    /// Reconnects in the background and reports through `Message::ReconnectFinished`.
+    pub fn reconnect_storage(&mut self, ctx: &egui::Context, name: String) {
+        if !self.reconnecting.insert(name.clone()) {
+            return;
+        }
+
+        let tx = self.tx_egui.clone();
+        let ctx = ctx.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = Rclone::reconnect(&name);
+            tx.send(Message::ReconnectFinished {
+                storage: name,
+                result,
+            })
+            .expect("Failed to send ReconnectFinished message");
+            ctx.request_repaint();
+        });
+    }
+
+    /// Lists the top of the storage, below its mount root, to see if it still works.
+    pub fn test_storage(&mut self, ctx: &egui::Context, name: String) {
+        if !self.testing.insert(name.clone()) {
+            return;
+        }
+
+        let remote = remote_path(&name, self.app_config.mount_roots.get(&name));
+        let tx = self.tx_egui.clone();
+        let ctx = ctx.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = Rclone::test_storage(&remote);
+            tx.send(Message::StorageTested {
+                storage: name,
+                result,
+            })
+            .expect("Failed to send StorageTested message");
+            ctx.request_repaint();
+        });
+    }
+
+    /// Switches to another rclone config file, `None` goes back to auto detection.
+    pub fn use_rclone_config_file(&mut self, ctx: &egui::Context, path: Option<PathBuf>) {
+        self.app_config.set_rclone_config_file(path);
+
+        let (config_file, config_file_source) =
+            detect_rclone_config_file(self.app_config.rclone_config_file.clone());
+        tracing::info!(
+            "Switched rclone config to {} ({})",
+            config_file.display(),
+            config_file_source.name()
+        );
+        set_rclone_config_file(config_file);
+        self.config_file_source = config_file_source;
+
+        self.rclone = Rclone::init();
+        self.spawn_config_watcher(ctx);
+    }
+
+    fn spawn_config_watcher(&mut self, ctx: &egui::Context) {
+        if let Some(handle) = self.config_watcher.take() {
+            handle.abort();
+        }
+
+        let config_file = rclone_config_file();
+        let tx_egui_clone_config = self.tx_egui.clone();
+        let ctx_clone_config = ctx.clone();
+        self.config_watcher = Some(tokio::spawn(async move {
+            let (tx_temp, mut rx_temp) = mpsc::unbounded_channel();
+            let mut watcher: RecommendedWatcher = RecommendedWatcher::new(
+                move |res| {
+                    let _ = tx_temp.send(res);
+                },
+                Config::default(),
+            )
+            .expect("Error creating watcher");
+
+            // editors often replace the file, so watch its directory instead of the inode
+            let config_dir = config_file
+                .parent()
+                .expect("Error getting rclone config directory");
+            watcher
+                .watch(config_dir, RecursiveMode::NonRecursive)
+                .expect("Error watching rclone config file");
+            loop {
+                match rx_temp.recv().await {
+                    Some(res) => match res {
+                        Ok(event) => {
+                            let is_config_file = event
+                                .paths
+                                .iter()
+                                .any(|path| path.file_name() == config_file.file_name());
+                            if is_config_file
+                                && (event.kind.is_modify()
+                                    || event.kind.is_create()
+                                    || event.kind.is_remove())
+                            {
+                                tx_egui_clone_config
+                                    .send(Message::RcloneConfigUpdated)
+                                    .expect("Error sending RcloneConfigUpdated message to egui");
+                                ctx_clone_config.request_repaint();
+                            }
+                        }
+                        Err(_) => {
+                            tracing::error!("Error watching rclone config file");
+                        }
+                    },
+                    None => {
+                        tracing::error!(
+                            "Error receiving message from rclone config watcher. Channel closed"
+                        );
+                        break;
+                    }
+                }
+            }
+        }));
+    }
 }
 
 impl eframe::App for DriveFUSE {
@@ -222,50 +685,7 @@ impl eframe::App for DriveFUSE {
             }
 
             // * Spawn rclone config watcher thread
-            let tx_egui_clone_config = self.tx_egui.clone();
-            let ctx_clone_config = ctx.clone();
-            tokio::spawn(async move {
-                let (tx_temp, mut rx_temp) = mpsc::unbounded_channel();
-                let mut watcher: RecommendedWatcher = RecommendedWatcher::new(
-                    move |res| {
-                        tx_temp
-                            .send(res)
-                            .expect("Error sending message to watcher thread");
-                    },
-                    Config::default(),
-                )
-                .expect("Error creating watcher");
-                watcher
-                    .watch(
-                        rclone_config_path()
-                            .expect("Error getting rclone config path")
-                            .as_path(),
-                        RecursiveMode::Recursive,
-                    )
-                    .expect("Error watching rclone config file");
-                loop {
-                    match rx_temp.recv().await {
-                        Some(res) => match res {
-                            Ok(event) => {
-                                if event.kind.is_modify() {
-                                    tx_egui_clone_config
-                                        .send(Message::RcloneConfigUpdated)
-                                        .expect(
-                                            "Error sending RcloneConfigUpdated message to egui",
-                                        );
-                                    ctx_clone_config.request_repaint();
-                                }
-                            }
-                            Err(_) => {
-                                tracing::error!("Error watching rclone config file");
-                            }
-                        },
-                        None => {
-                            tracing::error!("Error receiving message from rclone config watcher. Channel closed");
-                        }
-                    }
-                }
-            });
+            self.spawn_config_watcher(ctx);
 
             // * Auto mount drives on startup
             if self.app_config.is_auto_mount {
@@ -280,6 +700,7 @@ impl eframe::App for DriveFUSE {
                 self.mounted_storages.mount_all(
                     drives,
                     self.app_config.drives_letters.clone(),
+                    self.app_config.mount_roots.clone(),
                     self.app_config.enable_network_mode,
                 );
             }
@@ -324,6 +745,7 @@ impl eframe::App for DriveFUSE {
                     self.mounted_storages.mount_all(
                         self.rclone.storages.clone(),
                         self.app_config.drives_letters.clone(),
+                        self.app_config.mount_roots.clone(),
                         self.app_config.enable_network_mode,
                     );
 
@@ -341,6 +763,142 @@ impl eframe::App for DriveFUSE {
 
                     ctx.request_repaint();
                 }
+                Message::RcloneConfigFileSelected(path) => {
+                    tracing::info!("RcloneConfigFileSelected message received");
+
+                    self.use_rclone_config_file(ctx, Some(path));
+                }
+                Message::StorageOptionUpdated {
+                    storage,
+                    key,
+                    result,
+                } => {
+                    tracing::info!("StorageOptionUpdated message received");
+
+                    self.option_editor.finish_update(&storage, &key, result);
+                }
+                Message::ReconnectFinished { storage, result } => {
+                    tracing::info!("ReconnectFinished message received");
+
+                    self.reconnecting.remove(&storage);
+                    match result {
+                        Ok(()) => {
+                            self.token_failures.remove(&storage);
+                            self.storage_tests.remove(&storage);
+                            // pick up the new token
+                            self.rclone = Rclone::init();
+                        }
+                        Err(err) => {
+                            self.token_failures.insert(storage, err);
+                        }
+                    }
+                }
+                Message::StorageTested { storage, result } => {
+                    tracing::info!("StorageTested message received");
+
+                    self.testing.remove(&storage);
+                    self.storage_tests.insert(storage, result);
+                }
+                Message::BundleLoaded(result) => {
+                    tracing::info!("BundleLoaded message received");
+
+                    match result {
+                        Ok(bundle) => {
+                            let existing: Vec<String> = self
+                                .rclone
+                                .storages
+                                .iter()
+                                .map(|s| s.name.clone())
+                                .collect();
+                            self.bundle.load(bundle, &existing);
+                        }
+                        Err(err) => self.bundle.status = Some(Err(err)),
+                    }
+                }
+                Message::SharedDrivesListed { storage, result } => {
+                    tracing::info!("SharedDrivesListed message received");
+
+                    self.drive_options.finish_listing(&storage, result);
+                }
+                Message::BucketsListed { name, result } => {
+                    tracing::info!("BucketsListed message received");
+
+                    if let Some(wizard) = &mut self.create_wizard {
+                        if wizard.name == name {
+                            wizard.finish_listing(result);
+                        }
+                    }
+                }
+                Message::FormFilePicked { name, key, path } => {
+                    tracing::info!("FormFilePicked message received");
+
+                    if let Some(wizard) = &mut self.create_wizard {
+                        if wizard.name == name {
+                            wizard.values.insert(key, path.display().to_string());
+                        }
+                    }
+                }
+                Message::ConnectionTested { name, result } => {
+                    tracing::info!("ConnectionTested message received");
+
+                    if let Some(wizard) = &mut self.create_wizard {
+                        if wizard.name == name {
+                            wizard.finish_test(result);
+                        }
+                    }
+                }
+                Message::RemoteConfigStep { name, step } => {
+                    tracing::info!("RemoteConfigStep message received");
+
+                    let is_done = match &mut self.create_wizard {
+                        Some(wizard) if wizard.name == name => wizard.next_step(step),
+                        _ => false,
+                    };
+                    if is_done {
+                        self.finish_create_wizard();
+                    }
+                }
+                Message::RemoteCreated { name } => {
+                    tracing::info!("RemoteCreated message received");
+
+                    let is_done = match &mut self.create_wizard {
+                        Some(wizard) if wizard.name == name => wizard.next_step(ConfigStep::Done),
+                        _ => false,
+                    };
+                    if is_done {
+                        self.finish_create_wizard();
+                    }
+                }
+                Message::RemoteCreationFailed { name, error } => {
+                    tracing::info!("RemoteCreationFailed message received");
+
+                    match &mut self.create_wizard {
+                        Some(wizard) if wizard.name == name => wizard.fail(error),
+                        // the wizard was cancelled, its job killed on the way out
+                        _ => tracing::warn!("Creating {} stopped: {}", name, error),
+                    }
+                }
+                Message::SecretsExported { name } => {
+                    tracing::info!("SecretsExported message received");
+
+                    if let Some(wizard) = &mut self.create_wizard {
+                        if wizard.name == name {
+                            wizard.secrets_exported = true;
+                        }
+                    }
+                }
+                Message::BundleImported { imported, errors } => {
+                    tracing::info!("BundleImported message received");
+
+                    for (name, settings) in &imported {
+                        if let Some(settings) = settings {
+                            self.app_config.apply_settings(name, settings);
+                        }
+                    }
+
+                    self.bundle.finish_import(imported.len(), errors);
+                    self.rclone = Rclone::init();
+                }
             }
         }
 
@@ -354,6 +912,16 @@ impl eframe::App for DriveFUSE {
             Tab::Settings => render_settings(ctx, self),
         };
 
+        // * Remote creation wizard
+        if self.create_wizard.is_some() {
+            render_create_wizard(ctx, self);
+        }
+
+        // * Ask for the password of an encrypted rclone config
+        if self.rclone.is_locked {
+            render_unlock_config(ctx, self);
+        }
+
         // * Check if close requested
         if ctx.input(|i| i.viewport().close_requested()) {
             match self.is_close_requested {
@@ -2,10 +2,12 @@ use std::{
     collections::HashMap,
     fs::{self, File},
     io::{BufRead, BufReader, Write},
+    path::PathBuf,
 };
 
 use serde::{Deserialize, Serialize};
 
+use super::bundle::BundleSettings;
 use crate::utilities::{enums::AppTheme, utils::app_config_path};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -19,6 +21,13 @@ pub struct AppConfig {
 
     pub drives_letters: HashMap<String, char>,
     pub drives_auto_mount: HashMap<String, bool>,
+
+    #[serde(default)]
+    pub rclone_config_file: Option<PathBuf>,
+
+    /// Folder or bucket inside the remote that gets mounted instead of its root.
+    #[serde(default)]
+    pub mount_roots: HashMap<String, String>,
 }
 
 impl AppConfig {
@@ -42,6 +51,9 @@ impl AppConfig {
 
                 drives_letters: HashMap::new(),
                 drives_auto_mount: HashMap::new(),
+
+                rclone_config_file: None,
+                mount_roots: HashMap::new(),
             })
             .expect("Failed to serialize config");
             file.write_all(json.as_bytes()).expect("Failed to write to config file");
@@ -112,6 +124,58 @@ impl AppConfig {
         self.save();
     }
 
+    /// Moves every per-storage setting over to the storage's new name.
+    pub fn rename_drive(&mut self, old_name: &str, new_name: &str) {
+        if let Some(letter) = self.drives_letters.remove(old_name) {
+            self.drives_letters.insert(new_name.to_owned(), letter);
+        }
+        if let Some(auto_mount) = self.drives_auto_mount.remove(old_name) {
+            self.drives_auto_mount.insert(new_name.to_owned(), auto_mount);
+        }
+        if let Some(root) = self.mount_roots.remove(old_name) {
+            self.mount_roots.insert(new_name.to_owned(), root);
+        }
+        self.save();
+    }
+
+    /// Forgets every per-storage setting of a deleted storage.
+    pub fn remove_drive(&mut self, name: &str) {
+        self.drives_letters.remove(name);
+        self.drives_auto_mount.remove(name);
+        self.mount_roots.remove(name);
+        self.save();
+    }
+
+    /// Applies the settings of an imported or restored storage.
+    pub fn apply_settings(&mut self, name: &str, settings: &BundleSettings) {
+        if let Some(letter) = settings.drive_letter {
+            self.drives_letters.insert(name.to_owned(), letter);
+        }
+        if let Some(auto_mount) = settings.auto_mount {
+            self.drives_auto_mount.insert(name.to_owned(), auto_mount);
+        }
+        if let Some(root) = &settings.mount_root {
+            self.mount_roots.insert(name.to_owned(), root.clone());
+        }
+        self.save();
+    }
+
+    /// An empty root mounts the whole remote again.
+    pub fn set_mount_root(&mut self, key: String, root: String) {
+        let root = root.trim().trim_matches('/').to_owned();
+        if root.is_empty() {
+            self.mount_roots.remove(&key);
+        } else {
+            self.mount_roots.insert(key, root);
+        }
+        self.save();
+    }
+
+    pub fn set_rclone_config_file(&mut self, rclone_config_file: Option<PathBuf>) {
+        self.rclone_config_file = rclone_config_file;
+        self.save();
+    }
+
     pub fn get_drive_auto_mount(&self, key: &str) -> Option<bool> {
         self.drives_auto_mount.get(key).copied()
     }
@@ -0,0 +1,298 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    app_config::AppConfig,
+    rclone::{is_secret_option, Rclone, Storage},
+    rclone_conf::parse_config,
+};
+
+/// Remotes exported from one machine to be imported on another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub remotes: Vec<BundleRemote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleRemote {
+    pub name: String,
+    pub options: BTreeMap<String, String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<BundleSettings>,
+}
+
+/// DriveFUSE's own per-storage settings, which rclone.conf knows nothing about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drive_letter: Option<char>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_mount: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_root: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BundleFormat {
+    #[default]
+    Json,
+    Ini,
+}
+
+impl BundleFormat {
+    pub fn name(&self) -> &str {
+        match self {
+            BundleFormat::Json => "JSON",
+            BundleFormat::Ini => "INI",
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            BundleFormat::Json => "json",
+            BundleFormat::Ini => "conf",
+        }
+    }
+
+    pub fn values() -> [BundleFormat; 2] {
+        [BundleFormat::Json, BundleFormat::Ini]
+    }
+}
+
+/// What to do with one remote of an imported bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportAction {
+    Import,
+    Rename(String),
+    Skip,
+    Overwrite,
+}
+
+impl Bundle {
+    /// Secrets are left out unless asked for, so a bundle is safe to share by default.
+    pub fn export(
+        storages: &[Storage],
+        names: &HashSet<String>,
+        include_secrets: bool,
+        app_config: Option<&AppConfig>,
+    ) -> Self {
+        let remotes = storages
+            .iter()
+            .filter(|storage| names.contains(&storage.name))
+            .map(|storage| BundleRemote {
+                name: storage.name.clone(),
+                options: storage
+                    .options
+                    .iter()
+                    .filter(|(key, _)| include_secrets || !is_secret_option(key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+                settings: app_config.map(|app_config| BundleSettings {
+                    drive_letter: app_config.drives_letters.get(&storage.name).copied(),
+                    auto_mount: app_config.get_drive_auto_mount(&storage.name),
+                    mount_root: app_config.mount_roots.get(&storage.name).cloned(),
+                }),
+            })
+            .collect();
+
+        Self { remotes }
+    }
+
+    pub fn to_content(&self, format: BundleFormat) -> String {
+        match format {
+            BundleFormat::Json => {
+                serde_json::to_string_pretty(self).expect("Failed to serialize bundle")
+            }
+            // rclone.conf has no place for DriveFUSE's settings, so they are left out
+            BundleFormat::Ini => {
+                let mut content = String::new();
+                for remote in &self.remotes {
+                    content.push_str(&format!("[{}]\n", remote.name));
+                    for (key, value) in &remote.options {
+                        content.push_str(&format!("{} = {}\n", key, value));
+                    }
+                    content.push('\n');
+                }
+                content
+            }
+        }
+    }
+
+    /// Reads either format back, INI bundles are plain rclone.conf sections.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        if content.trim_start().starts_with('{') {
+            return serde_json::from_str(content).map_err(|err| format!("Invalid bundle: {}", err));
+        }
+
+        let (sections, errors) = parse_config(content);
+        if let Some(error) = errors.first() {
+            return Err(format!("Invalid bundle: {}", error));
+        }
+
+        Ok(Self {
+            remotes: sections
+                .into_iter()
+                .map(|section| BundleRemote {
+                    name: section.name,
+                    options: section.options.into_iter().collect(),
+                    settings: None,
+                })
+                .collect(),
+        })
+    }
+
+    /// Creates the remotes through rclone, returning the ones that made it in with the
+    /// name they ended up with, plus an error per remote that failed. An overwritten
+    /// remote is put back from `existing` when its replacement can't be created, it has to
+    /// be unmounted before this runs.
+    pub fn import(
+        &self,
+        actions: &[ImportAction],
+        existing: &[Storage],
+    ) -> (Vec<(String, Option<BundleSettings>)>, Vec<String>) {
+        let mut imported = vec![];
+        let mut errors = vec![];
+
+        for (remote, action) in self.remotes.iter().zip(actions) {
+            let (name, replaced) = match action {
+                ImportAction::Skip => continue,
+                ImportAction::Import => (remote.name.clone(), None),
+                ImportAction::Rename(name) => (name.clone(), None),
+                ImportAction::Overwrite => {
+                    let replaced = existing
+                        .iter()
+                        .find(|storage| storage.name == remote.name)
+                        .map(|storage| &storage.options);
+                    if let Err(err) = Rclone::delete_remote(&remote.name) {
+                        errors.push(format!("{}: {}", remote.name, err));
+                        continue;
+                    }
+                    (remote.name.clone(), replaced)
+                }
+            };
+
+            match Rclone::create_remote(&name, &remote.options) {
+                Ok(()) => imported.push((name, remote.settings.clone())),
+                Err(err) => {
+                    let restored = match replaced {
+                        Some(options) => match Rclone::create_remote(&name, options) {
+                            Ok(()) => ", the existing storage was kept".to_owned(),
+                            Err(restore_err) => {
+                                format!(", restoring the existing storage failed: {}", restore_err)
+                            }
+                        },
+                        None => String::new(),
+                    };
+                    errors.push(format!("{}: {}{}", name, err, restored));
+                }
+            }
+        }
+
+        (imported, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::utilities::enums::AppTheme;
+
+    fn storage(name: &str) -> Storage {
+        let options = [
+            ("type", "drive"),
+            ("scope", "drive"),
+            ("client_secret", "s3cr3t"),
+            ("token", r#"{"access_token":"x","refresh_token":"y"}"#),
+            ("root_folder_id", "abc="),
+        ];
+
+        Storage {
+            name: name.to_owned(),
+            drive_type: "drive".to_owned(),
+            scope: Some("drive".to_owned()),
+            token: None,
+            options: options
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        }
+    }
+
+    fn app_config() -> AppConfig {
+        AppConfig {
+            is_first_run: false,
+            is_auto_mount: false,
+            current_theme: AppTheme::Dark,
+            hide_storage_label: false,
+            enable_network_mode: false,
+            drives_letters: HashMap::from([("work".to_owned(), 'W')]),
+            drives_auto_mount: HashMap::from([("work".to_owned(), true)]),
+            rclone_config_file: None,
+            mount_roots: HashMap::from([("work".to_owned(), "Projects".to_owned())]),
+        }
+    }
+
+    fn export(include_secrets: bool, app_config: Option<&AppConfig>) -> Bundle {
+        let storages = [storage("work"), storage("home")];
+        let names = HashSet::from(["work".to_owned()]);
+        Bundle::export(&storages, &names, include_secrets, app_config)
+    }
+
+    #[test]
+    fn export_leaves_secrets_out_unless_asked_for() {
+        let bundle = export(false, None);
+        assert_eq!(bundle.remotes.len(), 1);
+        assert_eq!(bundle.remotes[0].name, "work");
+
+        let keys: Vec<&str> = bundle.remotes[0]
+            .options
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, ["root_folder_id", "scope", "type"]);
+
+        let bundle = export(true, None);
+        assert_eq!(bundle.remotes[0].options, storage("work").options);
+    }
+
+    #[test]
+    fn bundles_round_trip_in_both_formats() {
+        for include_secrets in [false, true] {
+            let bundle = export(include_secrets, None);
+            for format in BundleFormat::values() {
+                assert_eq!(
+                    Bundle::parse(&bundle.to_content(format)),
+                    Ok(bundle.clone()),
+                    "{}",
+                    format.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_json_bundles_keep_settings() {
+        let bundle = export(true, Some(&app_config()));
+        assert_eq!(
+            bundle.remotes[0].settings,
+            Some(BundleSettings {
+                drive_letter: Some('W'),
+                auto_mount: Some(true),
+                mount_root: Some("Projects".to_owned()),
+            })
+        );
+
+        let json = Bundle::parse(&bundle.to_content(BundleFormat::Json));
+        assert_eq!(json, Ok(bundle.clone()));
+
+        let ini = Bundle::parse(&bundle.to_content(BundleFormat::Ini)).expect("valid INI bundle");
+        assert_eq!(ini.remotes[0].settings, None);
+        assert_eq!(ini.remotes[0].options, bundle.remotes[0].options);
+    }
+}
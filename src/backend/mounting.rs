@@ -2,7 +2,10 @@ use std::{collections::HashMap, process::Command};
 
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::utilities::enums::Message;
+use crate::utilities::{
+    enums::Message,
+    utils::{rclone_command, remote_path},
+};
 
 #[cfg(target_os = "windows")]
 use {
@@ -74,6 +77,20 @@ impl MountingStorage {
         }
     }
 
+    /// Whether the storage was mounted by this app, as opposed to an external rclone.
+    pub fn is_tracked(&self, name: &str) -> bool {
+        self.drives.contains_key(name)
+    }
+
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if let Some(id) = self.drives.remove(old_name) {
+            self.drives.insert(new_name.to_owned(), id);
+        }
+        if let Some(letter) = self.mounted.remove(old_name) {
+            self.mounted.insert(new_name.to_owned(), letter);
+        }
+    }
+
     pub fn get_mounted(&self, name: String) -> Option<String> {
         self.mounted.get(&name).map(|c| c.to_string())
     }
@@ -82,6 +99,7 @@ impl MountingStorage {
         &mut self,
         drives: Vec<Storage>,
         _drives_letters: HashMap<String, char>,
+        mount_roots: HashMap<String, String>,
         _network_mode: bool,
     ) -> bool {
         #[cfg(target_os = "windows")]
@@ -92,8 +110,13 @@ impl MountingStorage {
                     .get(&drive.name)
                     .expect("Failed to get letter")
                     .to_string();
-                let id =
-                    Self::mount_windows(drive.name.clone(), letter.clone(), false, _network_mode);
+                let id = Self::mount_windows(
+                    drive.name.clone(),
+                    remote_path(&drive.name, mount_roots.get(&drive.name)),
+                    letter.clone(),
+                    false,
+                    _network_mode,
+                );
                 match id {
                     Some(id) => {
                         // available_drives.remove(0);
@@ -137,10 +160,10 @@ impl MountingStorage {
                         .create(format!("{}/{}/drive_fuse/{}", root, username, drive.name))
                         .expect("Failed to create directory");
                 }
-                let mut cmd = Command::new("rclone");
+                let mut cmd = rclone_command();
                 let process = cmd
                     .arg("mount")
-                    .arg(format!("{}:", drive.name))
+                    .arg(remote_path(&drive.name, mount_roots.get(&drive.name)))
                     .arg(format!(
                         "{}/{}/drive_fuse/{}",
                         root,
@@ -221,16 +244,19 @@ impl MountingStorage {
         _driver_letter: String,
         name: String,
         _show_terminal: bool,
-        _app_config: &mut AppConfig,
+        app_config: &mut AppConfig,
         tx: UnboundedSender<Message>,
     ) {
+        let remote = remote_path(&name, app_config.mount_roots.get(&name));
+
         #[cfg(target_os = "windows")]
         {
             let id = Self::mount_windows(
                 name.clone(),
+                remote,
                 _driver_letter.clone(),
                 _show_terminal,
-                _app_config.enable_network_mode,
+                app_config.enable_network_mode,
             );
             match id {
                 Some(id) => {
@@ -240,7 +266,7 @@ impl MountingStorage {
                         name.clone(),
                         _driver_letter.chars().next().expect("Failed to get letter"),
                     );
-                    _app_config.set_drives_letters(
+                    app_config.set_drives_letters(
                         name,
                         _driver_letter.chars().next().expect("Failed to get letter"),
                     );
@@ -257,7 +283,7 @@ impl MountingStorage {
         #[cfg(target_os = "linux")]
         {
             let username = whoami::username();
-            let id = Self::mount_unix(name.clone());
+            let id = Self::mount_unix(name.clone(), remote.clone());
             match id {
                 Some(id) => {
                     tracing::info!("Mounted {} to /home/{}/drive_fuse/{}", username, name, name);
@@ -280,7 +306,7 @@ impl MountingStorage {
         #[cfg(target_os = "macos")]
         {
             let username = whoami::username();
-            let id = Self::mount_unix(name.clone());
+            let id = Self::mount_unix(name.clone(), remote.clone());
             match id {
                 Some(id) => {
                     tracing::info!(
@@ -375,6 +401,7 @@ impl MountingStorage {
     #[cfg(target_os = "windows")]
     fn mount_windows(
         name: String,
+        remote: String,
         driver_letter: String,
         show_terminal: bool,
         network_mode: bool,
@@ -387,10 +414,10 @@ impl MountingStorage {
             .to_str()
             .expect("Failed to convert to string")
             .to_owned();
-        let mut cmd = Command::new("rclone");
+        let mut cmd = rclone_command();
         let process = cmd
             .arg("mount")
-            .arg(format!("{}:", name))
+            .arg(remote)
             .arg(format!("{}:", driver_letter))
             .arg("--vfs-cache-mode")
             .arg("full")
@@ -437,7 +464,7 @@ impl MountingStorage {
     }
 
     #[cfg(target_os = "linux")]
-    fn mount_unix(name: String) -> Option<u32> {
+    fn mount_unix(name: String, remote: String) -> Option<u32> {
         let username = whoami::username();
         if !Path::new(&format!("/home/{}/drive_fuse/{}", username.clone(), name)).exists() {
             DirBuilder::new()
@@ -446,10 +473,10 @@ impl MountingStorage {
                 .expect("Failed to create directory");
         }
 
-        let mut cmd = Command::new("rclone");
+        let mut cmd = rclone_command();
         let process = cmd
             .arg("mount")
-            .arg(format!("{}:", name))
+            .arg(remote)
             .arg(format!("/home/{}/drive_fuse/{}", username, name))
             .arg("--vfs-cache-mode")
             .arg("full");
@@ -472,7 +499,7 @@ impl MountingStorage {
     }
 
     #[cfg(target_os = "macos")]
-    fn mount_unix(name: String) -> Option<u32> {
+    fn mount_unix(name: String, remote: String) -> Option<u32> {
         let username = whoami::username();
         if !Path::new(&format!("/Users/{}/drive_fuse/{}", username.clone(), name)).exists() {
             DirBuilder::new()
@@ -480,10 +507,10 @@ impl MountingStorage {
                 .create(format!("/Users/{}/drive_fuse/{}", username, name))
                 .expect("Failed to create directory");
         }
-        let mut cmd = Command::new("rclone");
+        let mut cmd = rclone_command();
         let process = cmd
             .arg("mount")
-            .arg(format!("{}:", name))
+            .arg(remote)
             .arg(format!("/Users/{}/drive_fuse/{}", username, name))
             .arg("--vfs-cache-mode")
             .arg("full");
@@ -0,0 +1,266 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::utilities::{enums::CatalogSource, utils::rclone_command};
+
+/// Snapshot of `rclone config` backend list, used when rclone can't be asked.
+const BUNDLED_PROVIDERS: &str = include_str!("../../rclone_supported_drives.txt");
+
+/// A backend rclone can create remotes for, as reported by `rclone config providers`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Provider {
+    #[serde(rename = "Name")]
+    pub prefix: String,
+
+    #[serde(rename = "Description")]
+    pub description: String,
+
+    #[serde(rename = "Options", default)]
+    pub options: Vec<ProviderOption>,
+
+    #[serde(rename = "Hide", default)]
+    pub hide: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProviderOption {
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    #[serde(rename = "Help", default)]
+    pub help: String,
+
+    /// Comma separated list of the S3 style sub-providers the option applies to,
+    /// a leading `!` negates it.
+    #[serde(rename = "Provider", default)]
+    pub provider: String,
+
+    #[serde(rename = "Default", default)]
+    pub default: Value,
+
+    #[serde(rename = "Examples", default)]
+    pub examples: Option<Vec<ProviderExample>>,
+
+    #[serde(rename = "Required", default)]
+    pub required: bool,
+
+    #[serde(rename = "IsPassword", default)]
+    pub is_password: bool,
+
+    #[serde(rename = "Advanced", default)]
+    pub advanced: bool,
+
+    /// Only one of the examples may be picked.
+    #[serde(rename = "Exclusive", default)]
+    pub exclusive: bool,
+
+    /// rclone's type name, e.g. `bool`, `int`, `SizeSuffix` or `Duration`.
+    #[serde(rename = "Type", default)]
+    pub value_type: String,
+
+    /// Non zero when the option must not be asked for during config.
+    #[serde(rename = "Hide", default)]
+    pub hide: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProviderExample {
+    #[serde(rename = "Value")]
+    pub value: String,
+
+    #[serde(rename = "Help", default)]
+    pub help: String,
+
+    #[serde(rename = "Provider", default)]
+    pub provider: String,
+}
+
+impl ProviderOption {
+    /// Whether the option applies to the sub-provider chosen in the `provider` option.
+    pub fn applies_to(&self, provider: &str) -> bool {
+        applies_to(&self.provider, provider)
+    }
+
+    pub fn default_string(&self) -> String {
+        match &self.default {
+            Value::Null => String::new(),
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    pub fn examples_for(&self, provider: &str) -> Vec<&ProviderExample> {
+        self.examples
+            .iter()
+            .flatten()
+            .filter(|example| applies_to(&example.provider, provider))
+            .collect()
+    }
+
+    /// Checks a value against the option's type, an empty value means rclone's default.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return match self.required && self.default_string().is_empty() {
+                true => Err(format!("{} is required", self.name)),
+                false => Ok(()),
+            };
+        }
+
+        let is_valid = match self.value_type.as_str() {
+            "bool" => matches!(value, "true" | "false"),
+            "int" | "int64" | "uint32" => value.parse::<i64>().is_ok(),
+            "SizeSuffix" => {
+                let number = value.trim_end_matches(|c: char| "bBkKmMgGtTpPeEiI".contains(c));
+                number == "off" || number.parse::<f64>().is_ok()
+            }
+            "Duration" => {
+                value == "off"
+                    || (value.starts_with(|c: char| c.is_ascii_digit())
+                        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '.'))
+            }
+            _ => true,
+        };
+        if !is_valid {
+            return Err(format!("{} must be a {}", self.name, self.value_type));
+        }
+
+        if self.exclusive && !self.examples.iter().flatten().any(|e| e.value == value) {
+            return Err(format!("{} must be one of the listed values", self.name));
+        }
+
+        Ok(())
+    }
+}
+
+/// rclone's provider filter, a comma separated list optionally negated with `!`.
+fn applies_to(filter: &str, provider: &str) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+
+    match filter.strip_prefix('!') {
+        Some(filter) => !filter.split(',').any(|p| p.trim() == provider),
+        None => filter.split(',').any(|p| p.trim() == provider),
+    }
+}
+
+impl Provider {
+    /// Short label for lists, rclone's descriptions tend to run on.
+    pub fn display_name(&self) -> &str {
+        let description = self.description.trim();
+        let end = [" (", " including ", ", "]
+            .iter()
+            .filter_map(|sep| description.find(sep))
+            .min()
+            .unwrap_or(description.len());
+
+        match &description[..end] {
+            "" => &self.prefix,
+            name => name,
+        }
+    }
+
+    pub fn option(&self, name: &str) -> Option<&ProviderOption> {
+        self.options.iter().find(|option| option.name == name)
+    }
+
+    /// Backends that sign in through OAuth keep the result in their `token` option.
+    pub fn uses_oauth(&self) -> bool {
+        self.option("token").is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderCatalog {
+    pub providers: Vec<Provider>,
+    pub source: CatalogSource,
+}
+
+impl ProviderCatalog {
+    /// Asks the installed rclone for its backends, falling back to the bundled list.
+    pub fn load() -> Self {
+        match Self::query_rclone() {
+            Ok(providers) => {
+                tracing::info!("Loaded {} providers from rclone", providers.len());
+
+                Self {
+                    providers,
+                    source: CatalogSource::Rclone,
+                }
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "rclone config providers failed, using bundled list: {}",
+                    err
+                );
+
+                Self {
+                    providers: Self::parse_bundled(BUNDLED_PROVIDERS),
+                    source: CatalogSource::Bundled,
+                }
+            }
+        }
+    }
+
+    fn query_rclone() -> Result<Vec<Provider>, String> {
+        let output = rclone_command()
+            .args(["config", "providers"])
+            .output()
+            .map_err(|err| err.to_string())?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+        }
+
+        let mut providers: Vec<Provider> = serde_json::from_slice(&output.stdout)
+            .map_err(|err| format!("invalid rclone config providers: {}", err))?;
+
+        providers.retain(|provider| !provider.hide);
+        providers.sort_by_key(|provider| provider.display_name().to_lowercase());
+
+        Ok(providers)
+    }
+
+    /// Reads the menu printed by `rclone config`, pairs of ` N / Description` and
+    /// `   \ (prefix)` lines.
+    fn parse_bundled(content: &str) -> Vec<Provider> {
+        let mut providers = Vec::new();
+        let mut description: Option<&str> = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if let Some(prefix) = line
+                .strip_prefix("\\ (")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                if let Some(description) = description.take() {
+                    providers.push(Provider {
+                        prefix: prefix.to_owned(),
+                        description: description.to_owned(),
+                        options: Vec::new(),
+                        hide: false,
+                    });
+                }
+            } else if let Some((_, rest)) = line.split_once(" / ") {
+                description = Some(rest.trim());
+            }
+        }
+
+        providers
+    }
+
+    pub fn get(&self, prefix: &str) -> Option<&Provider> {
+        self.providers
+            .iter()
+            .find(|provider| provider.prefix == prefix)
+    }
+
+    /// Label for a remote's `type`, the raw type when rclone doesn't know it.
+    pub fn display_name<'a>(&'a self, prefix: &'a str) -> &'a str {
+        self.get(prefix)
+            .map(|provider| provider.display_name())
+            .unwrap_or(prefix)
+    }
+}
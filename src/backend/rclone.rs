@@ -1,34 +1,51 @@
-use chrono::{DateTime, FixedOffset};
-use serde_json::Value;
-
-#[cfg(target_os = "windows")]
-use {std::os::windows::process::CommandExt, winapi::um::winbase};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
 
 use std::{
+    collections::BTreeMap,
     fs::OpenOptions,
     io::{BufRead, BufReader, Write},
-    process::Command,
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::Instant,
 };
 
+use super::{
+    rclone_conf::{is_encrypted_config, parse_config, ConfigDocument, ConfigError, ConfigSection},
+    remote_config::CONFIG_TIMEOUT,
+};
 use crate::utilities::{
-    enums::StorageType,
+    enums::{ConfigSource, TokenState},
     utils::{
-        add_dropbox_storage, add_google_drive_storage, add_google_photos_storage,
-        add_nextcloud_storage, add_onedrive_storage, app_config_path, rclone_config_path,
+        app_config_path, has_rclone_config_pass, output_with_timeout, rclone_command,
+        rclone_config_file, set_rclone_config_pass, write_private_file,
     },
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rclone {
     pub storages: Vec<Storage>,
+    pub errors: Vec<ConfigError>,
+    pub is_encrypted: bool,
+    pub is_locked: bool,
+    pub source: ConfigSource,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Storage {
     pub name: String,
     pub drive_type: String,
-    pub scope: String,
-    pub token: TokenStruct,
+    pub scope: Option<String>,
+    pub token: Option<TokenStruct>,
+    pub options: BTreeMap<String, String>,
+}
+
+/// A Google shared drive (formerly team drive) the signed in user can access.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SharedDrive {
+    pub id: String,
+    pub name: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -39,182 +56,555 @@ pub struct TokenStruct {
     pub expiry: DateTime<FixedOffset>,
 }
 
-impl Rclone {
-    pub fn init() -> Self {
-        let mut rclone = Self { storages: vec![] };
+/// Options holding credentials, which are masked wherever they are shown.
+pub fn is_secret_option(key: &str) -> bool {
+    const SECRETS: [&str; 8] = [
+        "key",
+        "key_pem",
+        "sas_url",
+        "client_secret",
+        "service_account_credentials",
+        "app_key",
+        "account_key",
+        "api_key",
+    ];
+
+    SECRETS.contains(&key)
+        || key.contains("pass")
+        || key.contains("secret")
+        || key.contains("token")
+}
+
+impl Storage {
+    /// Builds a storage from every `key = value` of a remote. Only `type` is mandatory,
+    /// a broken OAuth token is reported but the remote is still listed.
+    fn from_section(section: ConfigSection, errors: &mut Vec<ConfigError>) -> Option<Self> {
+        let options: BTreeMap<String, String> = section.options.into_iter().collect();
+
+        let drive_type = match options.get("type") {
+            Some(val) if !val.is_empty() => val.to_owned(),
+            _ => {
+                errors.push(ConfigError::new(
+                    Some(section.name),
+                    None,
+                    "remote has no `type`",
+                ));
+                return None;
+            }
+        };
+
+        let token = match options.get("token") {
+            Some(val) if !val.is_empty() => match TokenStruct::parse(val) {
+                Ok(token) => Some(token),
+                Err(err) => {
+                    errors.push(ConfigError::new(Some(section.name.clone()), None, err));
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        Some(Self {
+            name: section.name,
+            drive_type,
+            scope: options.get("scope").cloned(),
+            token,
+            options,
+        })
+    }
+}
 
-        rclone.storages = rclone.parse_storages();
+impl TokenStruct {
+    /// How long before the expiry a token is reported as expiring.
+    const EXPIRING_WINDOW_MINUTES: i64 = 10;
 
-        rclone
+    pub fn has_refresh_token(&self) -> bool {
+        !self.refresh_token.is_empty()
     }
 
-    fn read_config() -> Vec<String> {
-        let rclone_config_path = rclone_config_path()
-            .expect("Failed to get rclone config path")
-            .join("rclone.conf");
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(false)
-            .read(true)
-            .open(rclone_config_path)
-            .expect("Failed to open rclone config file");
-        let buffered = BufReader::new(file);
-
-        let mut lines: Vec<String> = Vec::new();
-
-        for line in buffered.lines() {
-            lines.push(line.expect("Failed to read line"));
+    /// Only a token without a refresh token runs out, rclone renews the short lived access
+    /// token of the others on use. Whether that renewal still works takes a real request.
+    pub fn state(&self) -> TokenState {
+        // some backends hand out tokens that never expire and store a zero expiry
+        if self.has_refresh_token() || self.expiry.year() <= 1 {
+            return TokenState::Valid;
         }
 
-        tracing::info!("Read app config");
+        let now = Utc::now();
+        if self.expiry <= now {
+            TokenState::Expired
+        } else if self.expiry <= now + Duration::minutes(Self::EXPIRING_WINDOW_MINUTES) {
+            TokenState::Expiring
+        } else {
+            TokenState::Valid
+        }
+    }
+
+    /// Pulls the token JSON out of what `rclone authorize` printed on another machine,
+    /// returning it ready to be handed to rclone.
+    pub fn from_authorize_output(output: &str) -> Result<String, String> {
+        let token = output.split_once("--->").map_or(output, |(_, rest)| rest);
+        let token = token
+            .split_once("<---End paste")
+            .map_or(token, |(token, _)| token)
+            .trim();
+
+        let parsed = Self::parse(token)?;
+        if parsed.access_token.is_empty() {
+            return Err("token has no access_token".to_owned());
+        }
+
+        Ok(token.to_owned())
+    }
 
-        lines
+    fn parse(input: &str) -> Result<Self, String> {
+        let json: Value =
+            serde_json::from_str(input).map_err(|err| format!("invalid token json: {}", err))?;
+
+        let field = |key: &str| json[key].as_str().unwrap_or_default().to_owned();
+
+        let expiry = DateTime::parse_from_rfc3339(&field("expiry"))
+            .map_err(|err| format!("invalid token expiry: {}", err))?;
+
+        Ok(Self {
+            access_token: field("access_token"),
+            token_type: field("token_type"),
+            refresh_token: field("refresh_token"),
+            expiry,
+        })
     }
+}
+
+impl Rclone {
+    /// How long a connection test may take before it counts as failed.
+    pub const TEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
 
-    fn write_config(lines: Vec<String>, file_name: Option<String>) {
-        let rclone_config_path = rclone_config_path()
-            .expect("Failed to get rclone config path")
-            .join({
-                match file_name.clone() {
-                    Some(val) => val,
-                    None => "rclone.conf".to_string(),
+    pub fn init() -> Self {
+        let (content, read_error) = match Self::read_config() {
+            Ok(content) => (content, None),
+            Err(err) => (String::new(), Some(ConfigError::new(None, None, err))),
+        };
+        let is_encrypted = is_encrypted_config(&content);
+
+        let (storages, errors, is_locked, source) = if is_encrypted && !has_rclone_config_pass() {
+            (vec![], vec![], true, ConfigSource::File)
+        } else {
+            match Self::dump_storages() {
+                Ok((storages, errors)) => (storages, errors, false, ConfigSource::RcloneDump),
+                Err(err) if is_encrypted => (
+                    vec![],
+                    vec![ConfigError::new(None, None, err)],
+                    true,
+                    ConfigSource::File,
+                ),
+                Err(err) => {
+                    tracing::warn!(
+                        "rclone config dump failed, parsing the config file instead: {}",
+                        err
+                    );
+                    let (storages, errors) = Self::parse_storages(&content);
+                    (storages, errors, false, ConfigSource::File)
                 }
-            });
-        let file = OpenOptions::new()
-            .write(true)
-            .create(file_name.is_some())
-            .truncate(true)
-            .open(rclone_config_path)
-            .expect("Failed to open rclone config file");
-        let mut buffered = BufReader::new(file);
-
-        for line in lines {
-            buffered
-                .get_mut()
-                .write_all(line.as_bytes())
-                .expect("couldnt write to file");
-            buffered
-                .get_mut()
-                .write_all("\n".as_bytes())
-                .expect("couldnt write to file");
+            }
+        };
+
+        let errors: Vec<ConfigError> = read_error.into_iter().chain(errors).collect();
+        for error in &errors {
+            tracing::error!("Error in rclone config: {}", error);
+        }
+
+        Self {
+            storages,
+            errors,
+            is_encrypted,
+            is_locked,
+            source,
         }
     }
 
-    fn parse_storages(&self) -> Vec<Storage> {
-        let mut drive_name = String::new();
-        let mut drive_type = String::new();
-        let mut drive_scope = String::new();
-        let mut drive_token: Option<TokenStruct> = None;
-
-        let mut storages: Vec<Storage> = Vec::new();
-
-        for line in Self::read_config() {
-            if line.is_empty() {
-                storages.push(Storage {
-                    name: drive_name.clone(),
-                    drive_type: drive_type.clone(),
-                    scope: drive_scope.clone(),
-                    token: match drive_token {
-                        Some(ref val) => val.clone(),
-                        None => panic!("didnt get token for driver"),
-                    },
-                });
-            } else if line.starts_with('[') {
-                line.get(1..line.len() - 1)
-                    .expect("couldnt get drive name")
-                    .clone_into(&mut drive_name);
-            } else {
-                match line.get(..2) {
-                    Some("ty") => line
-                        .split('=')
-                        .last()
-                        .expect("couldnt get drive type")
-                        .replace(' ', "")
-                        .clone_into(&mut drive_type),
-                    Some("sc") => line
-                        .split('=')
-                        .last()
-                        .expect("couldnt get drive scope")
-                        .replace(' ', "")
-                        .clone_into(&mut drive_scope),
-                    Some("to") => {
-                        let input = line.split('=').last().expect("couldnt get token");
-                        let json: Value =
-                            serde_json::from_str(input).expect("couldnt parse token json");
-                        drive_token = Some(TokenStruct {
-                            access_token: json["access_token"].to_string().replace('\"', ""),
-                            token_type: json["token_type"].to_string().replace('\"', ""),
-                            refresh_token: json["refresh_token"].to_string().replace('\"', ""),
-                            expiry: DateTime::parse_from_rfc3339(
-                                &json["expiry"].to_string().replace('\"', ""),
-                            )
-                            .expect("couldnt parse expiry"),
-                        });
-                    }
-                    _ => continue,
+    /// Tries the password against the encrypted config and keeps it in memory on success.
+    pub fn unlock(&mut self, password: String) -> Result<(), String> {
+        set_rclone_config_pass(Some(password));
+
+        let rclone = Self::init();
+        if rclone.is_locked {
+            set_rclone_config_pass(None);
+            tracing::error!("Failed to unlock encrypted rclone config");
+            return Err(rclone
+                .errors
+                .first()
+                .map(|err| err.to_string())
+                .unwrap_or_else(|| "Wrong password".to_owned()));
+        }
+
+        tracing::info!("Unlocked encrypted rclone config");
+        *self = rclone;
+        Ok(())
+    }
+
+    /// Lets rclone resolve the config itself, which covers env overrides and encryption.
+    fn dump_storages() -> Result<(Vec<Storage>, Vec<ConfigError>), String> {
+        let output = rclone_command()
+            .args(["config", "dump"])
+            .output()
+            .map_err(|err| err.to_string())?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+        }
+
+        let json: Map<String, Value> = serde_json::from_slice(&output.stdout)
+            .map_err(|err| format!("invalid rclone config dump: {}", err))?;
+
+        let mut errors: Vec<ConfigError> = Vec::new();
+        let mut storages: Vec<Storage> = Vec::with_capacity(json.len());
+
+        for (name, remote) in json {
+            let options = match remote {
+                Value::Object(options) => options
+                    .into_iter()
+                    .map(|(key, value)| match value {
+                        Value::String(value) => (key, value),
+                        value => (key, value.to_string()),
+                    })
+                    .collect(),
+                _ => {
+                    errors.push(ConfigError::new(
+                        Some(name),
+                        None,
+                        "remote is not an object",
+                    ));
+                    continue;
                 }
+            };
+
+            if let Some(storage) =
+                Storage::from_section(ConfigSection { name, options }, &mut errors)
+            {
+                storages.push(storage);
+            }
+        }
+
+        tracing::info!("Loaded rclone config from rclone config dump");
+
+        Ok((storages, errors))
+    }
+
+    /// Reads the config without ever creating it, a missing file is just an empty config.
+    fn read_config() -> Result<String, String> {
+        let rclone_config_path = rclone_config_file();
+        let content = match std::fs::read_to_string(&rclone_config_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(format!(
+                    "Failed to read {}: {}",
+                    rclone_config_path.display(),
+                    err
+                ))
+            }
+        };
+
+        tracing::info!("Read rclone config");
+
+        Ok(content)
+    }
+
+    /// Replaces the file in one step, so a crash or a full disk never leaves a half written
+    /// config. Like rclone, only the user can read it.
+    fn write_config(content: &str, file_name: Option<String>) -> Result<(), String> {
+        let rclone_config_path = match file_name {
+            Some(val) => PathBuf::from(val),
+            None => rclone_config_file(),
+        };
+
+        write_private_file(&rclone_config_path, content.as_bytes())
+            .map_err(|err| format!("Failed to write {}: {}", rclone_config_path.display(), err))
+    }
+
+    /// Applies a structured edit to the config file, leaving every other byte untouched.
+    pub fn edit_config(edit: impl FnOnce(&mut ConfigDocument) -> bool) -> Result<(), String> {
+        let content = Self::read_config()?;
+        if is_encrypted_config(&content) {
+            return Err("The rclone config is encrypted and cannot be edited directly".to_owned());
+        }
+
+        let mut document = ConfigDocument::parse(&content);
+        if !edit(&mut document) {
+            return Err("Nothing to change in the rclone config".to_owned());
+        }
+
+        Self::write_config(&document.to_string(), None)?;
+        tracing::info!("Edited rclone config");
+
+        Ok(())
+    }
+
+    fn parse_storages(content: &str) -> (Vec<Storage>, Vec<ConfigError>) {
+        let (sections, mut errors) = parse_config(content);
+
+        let mut storages: Vec<Storage> = Vec::with_capacity(sections.len());
+
+        for section in sections {
+            if let Some(storage) = Storage::from_section(section, &mut errors) {
+                storages.push(storage);
             }
         }
 
         tracing::info!("Parsed rclone config file");
 
-        storages
+        (storages, errors)
     }
 
-    pub fn add_storage(&mut self, name: String, storage_type: StorageType) {
-        match storage_type {
-            StorageType::GoogleDrive => add_google_drive_storage(name),
-            StorageType::OneDrive => add_onedrive_storage(name),
-            StorageType::Dropbox => add_dropbox_storage(name),
-            StorageType::GooglePhotos => add_google_photos_storage(name),
-            // StorageType::Mega => add_mega_storage(name),
-            StorageType::NextCloud => add_nextcloud_storage(name),
+    /// Sets a single option through rclone, an empty value clears it. rclone obscures
+    /// passwords itself and `--non-interactive` keeps OAuth backends from prompting.
+    pub fn update_option(name: &str, key: &str, value: &str) -> Result<(), String> {
+        let output = rclone_command()
+            .args(["config", "update", name, key, value, "--non-interactive"])
+            .output()
+            .map_err(|err| err.to_string())?;
+
+        if output.status.success() {
+            tracing::info!("Updated option {} of storage {}", key, name);
+            Ok(())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            tracing::error!(
+                "Error updating option {} of storage {}: {}",
+                key,
+                name,
+                error
+            );
+            Err(error)
         }
     }
 
-    pub fn edit_storage_name(&mut self, old_name: String, new_name: String) {
-        let lines = Self::read_config();
+    /// Creates a remote from options as they are stored in a config, so secrets are
+    /// expected to be obscured already and OAuth tokens are kept as they are.
+    pub fn create_remote(name: &str, options: &BTreeMap<String, String>) -> Result<(), String> {
+        let drive_type = options
+            .get("type")
+            .ok_or_else(|| format!("{} has no type", name))?;
+
+        let mut cmd = rclone_command();
+        cmd.args(["config", "create", name, drive_type]);
+        for (key, value) in options.iter().filter(|(key, _)| key.as_str() != "type") {
+            cmd.arg(key).arg(value);
+        }
+        cmd.args(["--non-interactive", "--no-obscure"]);
+
+        let output = cmd.output().map_err(|err| err.to_string())?;
+        if output.status.success() {
+            tracing::info!("Created storage {}", name);
+            Ok(())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            tracing::error!("Error creating storage {}: {}", name, error);
+            Err(error)
+        }
+    }
 
-        let mut new_lines: Vec<String> = Vec::new();
+    pub fn delete_remote(name: &str) -> Result<(), String> {
+        let output = rclone_command()
+            .args(["config", "delete", name])
+            .output()
+            .map_err(|err| err.to_string())?;
+
+        if output.status.success() {
+            tracing::info!("Deleted storage {}", name);
+            Ok(())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            tracing::error!("Error deleting storage {}: {}", name, error);
+            Err(error)
+        }
+    }
 
-        let mut is_updated = false;
+    /// Runs the OAuth flow again for a remote whose token can no longer be refreshed. Gives
+    /// up after the same time the creation wizard allows for signing in.
+    pub fn reconnect(name: &str) -> Result<(), String> {
+        let mut cmd = rclone_command();
+        cmd.args([
+            "config",
+            "reconnect",
+            &format!("{}:", name),
+            "--auto-confirm",
+        ]);
+        let output = output_with_timeout(&mut cmd, CONFIG_TIMEOUT)?;
+
+        if output.status.success() {
+            tracing::info!("Reconnected storage {}", name);
+            Ok(())
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            tracing::error!("Error reconnecting storage {}: {}", name, error);
+            Err(error)
+        }
+    }
 
-        for line in lines {
-            if line.starts_with('[') && !is_updated {
-                if line.get(1..line.len() - 1).expect("couldnt get drive name") == old_name {
-                    new_lines.push(format!("[{}]", new_name));
-                    is_updated = true;
-                } else {
-                    new_lines.push(line);
-                }
-            } else {
-                new_lines.push(line);
-            }
+    /// Obscures a password the way rclone stores it, read from stdin so it never shows up
+    /// in the process list.
+    pub fn obscure(value: &str) -> Result<String, String> {
+        let mut child = rclone_command()
+            .args(["obscure", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| err.to_string())?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(value.as_bytes())
+                .map_err(|err| err.to_string())?;
+        }
+
+        let output = child.wait_with_output().map_err(|err| err.to_string())?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_owned())
         }
+    }
 
-        match is_updated {
-            true => Self::write_config(new_lines, None),
-            false => tracing::error!("Error updating storage name"),
+    /// rclone command for an on the fly `:backend:` remote, passing its options as
+    /// `RCLONE_<BACKEND>_*` environment variables so nothing is saved.
+    fn backend_command(backend: &str, options: &BTreeMap<String, String>) -> Command {
+        let mut cmd = rclone_command();
+        for (key, value) in options.iter().filter(|(key, _)| key.as_str() != "type") {
+            let var = format!("RCLONE_{}_{}", backend, key)
+                .to_uppercase()
+                .replace(['-', ' '], "_");
+            cmd.env(var, value);
         }
+        cmd
     }
 
-    pub fn remove_storage(&mut self, name: String) {
-        let mut cmd = Command::new("rclone");
-        let output = cmd.arg("config").arg("delete").arg(name.clone());
+    /// Lists the root of a not yet saved remote, returning how long rclone took.
+    pub fn test_backend(
+        backend: &str,
+        options: &BTreeMap<String, String>,
+    ) -> Result<std::time::Duration, String> {
+        let mut cmd = Self::backend_command(backend, options);
+        cmd.args(["lsd", &format!(":{}:", backend), "--max-depth", "1"]);
+
+        let started = Instant::now();
+        let output = output_with_timeout(&mut cmd, Self::TEST_TIMEOUT)?;
+        if output.status.success() {
+            Ok(started.elapsed())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_owned())
+        }
+    }
 
-        #[cfg(target_os = "windows")]
-        output.creation_flags(winbase::CREATE_NO_WINDOW);
+    /// Lists the top of a saved remote like `gdrive:Projects`, returning how long rclone
+    /// took or the last line of its error output.
+    pub fn test_storage(remote_path: &str) -> Result<std::time::Duration, String> {
+        let mut cmd = rclone_command();
+        cmd.args(["lsd", remote_path, "--max-depth", "1"]);
 
-        let output = output.output().expect("failed to execute process");
-        match String::from_utf8(output.stdout) {
-            Ok(_) => self.storages.retain(|storage| storage.name != name),
-            Err(_) => tracing::error!("Error removing storage"),
+        let started = Instant::now();
+        let output = output_with_timeout(&mut cmd, Self::TEST_TIMEOUT)?;
+        if output.status.success() {
+            return Ok(started.elapsed());
         }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        tracing::error!("Error testing {}: {}", remote_path, stderr.trim());
+
+        // rclone prefixes its log lines with "<date> <time> ERROR : "
+        let error = stderr
+            .lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| line.rsplit_once("ERROR : ").map_or(line, |(_, msg)| msg))
+            .unwrap_or("rclone failed without output");
+        Err(error.to_owned())
+    }
+
+    /// Folder names at `path` of a not yet saved remote, the buckets when `path` is empty.
+    pub fn list_backend_dirs(
+        backend: &str,
+        options: &BTreeMap<String, String>,
+        path: &str,
+    ) -> Result<Vec<String>, String> {
+        let mut cmd = Self::backend_command(backend, options);
+        cmd.args(["lsjson", &format!(":{}:{}", backend, path), "--dirs-only"]);
+
+        let output = output_with_timeout(&mut cmd, Self::TEST_TIMEOUT)?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+        }
+
+        Self::parse_lsjson_names(&output.stdout)
+    }
+
+    /// Lists the folders of a configured remote path like `gdrive:Projects`.
+    pub fn list_dirs(remote_path: &str) -> Result<Vec<String>, String> {
+        let mut cmd = rclone_command();
+        cmd.args(["lsjson", remote_path, "--dirs-only"]);
+
+        let output = output_with_timeout(&mut cmd, Self::TEST_TIMEOUT)?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            tracing::error!("Error listing folders of {}: {}", remote_path, error);
+            return Err(error);
+        }
+
+        Self::parse_lsjson_names(&output.stdout)
+    }
+
+    fn parse_lsjson_names(stdout: &[u8]) -> Result<Vec<String>, String> {
+        let entries: Vec<Value> = serde_json::from_slice(stdout)
+            .map_err(|err| format!("invalid rclone lsjson output: {}", err))?;
+
+        let mut names: Vec<String> = entries
+            .iter()
+            .filter_map(|entry| entry["Name"].as_str().map(str::to_owned))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Lists the shared drives of a Google Drive remote with `rclone backend drives`.
+    pub fn list_shared_drives(name: &str) -> Result<Vec<SharedDrive>, String> {
+        let output = rclone_command()
+            .args(["backend", "drives", &format!("{}:", name)])
+            .output()
+            .map_err(|err| err.to_string())?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            tracing::error!("Error listing shared drives of {}: {}", name, error);
+            return Err(error);
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|err| format!("invalid shared drive list: {}", err))
+    }
+
+    /// Drops the `key = value` line. rclone has no command for that, so an encrypted config
+    /// gets an empty value instead, which rclone treats the same as a missing key.
+    pub fn remove_option(name: &str, key: &str) -> Result<(), String> {
+        if is_encrypted_config(&Self::read_config()?) {
+            return Self::update_option(name, key, "");
+        }
+
+        Self::edit_config(|document| document.remove_option(name, key))?;
+        tracing::info!("Removed option {} of storage {}", key, name);
+
+        Ok(())
     }
 
     pub fn create_backup(&self) {
-        let lines = Self::read_config();
+        let content = match Self::read_config() {
+            Ok(content) => content,
+            Err(err) => {
+                tracing::error!("Error backing up rclone config: {}", err);
+                return;
+            }
+        };
         // let datetime = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
         // Self::write_config(lines, Some(format!("rclone_{}.conf", datetime)));
         tokio::spawn(async move {
@@ -225,10 +615,12 @@ impl Rclone {
                 .await;
 
             if let Some(path) = res {
-                Self::write_config(
-                    lines,
+                if let Err(err) = Self::write_config(
+                    &content,
                     Some(path.path().to_str().expect("couldnt get path").to_owned()),
-                );
+                ) {
+                    tracing::error!("Error backing up rclone config: {}", err);
+                }
             }
         });
     }
@@ -246,11 +638,7 @@ impl Rclone {
                     .write(true)
                     .create(true)
                     .truncate(false)
-                    .open(
-                        rclone_config_path()
-                            .expect("couldnt get rclone config path")
-                            .join("rclone.conf"),
-                    )
+                    .open(rclone_config_file())
                     .expect("couldnt open file");
                 let mut buffered = BufReader::new(file);
 
@@ -0,0 +1,676 @@
+use std::fmt;
+
+/// A `[name]` section of an rclone config file with its `key = value` pairs in file order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSection {
+    pub name: String,
+    pub options: Vec<(String, String)>,
+}
+
+impl ConfigSection {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A problem found while reading the config, tied to the remote it belongs to (if any).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub remote: Option<String>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub fn new(remote: Option<String>, line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            remote,
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.remote, self.line) {
+            (Some(remote), Some(line)) => write!(f, "[{}] line {}: {}", remote, line, self.message),
+            (Some(remote), None) => write!(f, "[{}] {}", remote, self.message),
+            (None, Some(line)) => write!(f, "line {}: {}", line, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// rclone replaces the whole file with this marker followed by the ciphertext.
+pub fn is_encrypted_config(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| line.trim() == "RCLONE_ENCRYPT_V0:")
+}
+
+/// What a single trimmed line of rclone.conf contains.
+enum ParsedLine<'a> {
+    Empty,
+    Section(&'a str),
+    Option(&'a str, &'a str),
+    Invalid(String),
+}
+
+fn parse_line(line: &str) -> ParsedLine<'_> {
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+        return ParsedLine::Empty;
+    }
+
+    if line.starts_with('[') {
+        return match line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            Some(name) if name.trim().is_empty() => {
+                ParsedLine::Invalid("section header has no name".to_owned())
+            }
+            Some(name) => ParsedLine::Section(name.trim()),
+            None => ParsedLine::Invalid(format!("unterminated section header `{}`", line)),
+        };
+    }
+
+    match line.split_once('=') {
+        Some((key, _)) if key.trim().is_empty() => {
+            ParsedLine::Invalid("option has no key".to_owned())
+        }
+        Some((key, value)) => ParsedLine::Option(key.trim(), unquote(value.trim())),
+        None => ParsedLine::Invalid(format!("expected `key = value`, got `{}`", line)),
+    }
+}
+
+/// Parses the INI dialect used by rclone.conf.
+///
+/// Blank lines and `#`/`;` comments are ignored, keys may appear in any order and the
+/// value is everything after the first `=`. Malformed lines are reported and skipped so
+/// one broken remote never hides the others.
+pub fn parse_config(content: &str) -> (Vec<ConfigSection>, Vec<ConfigError>) {
+    let mut sections: Vec<ConfigSection> = Vec::new();
+    let mut errors: Vec<ConfigError> = Vec::new();
+    let mut current: Option<usize> = None;
+
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        let remote = current.map(|pos| sections[pos].name.clone());
+
+        match parse_line(line) {
+            ParsedLine::Empty => {}
+            ParsedLine::Section(name) => {
+                // rclone merges repeated sections, so do the same
+                current = match sections.iter().position(|s| s.name == name) {
+                    Some(pos) => Some(pos),
+                    None => {
+                        sections.push(ConfigSection {
+                            name: name.to_owned(),
+                            options: Vec::new(),
+                        });
+                        Some(sections.len() - 1)
+                    }
+                };
+            }
+            ParsedLine::Option(key, value) => match current {
+                Some(pos) => sections[pos]
+                    .options
+                    .push((key.to_owned(), value.to_owned())),
+                None => errors.push(ConfigError::new(
+                    None,
+                    Some(line_no),
+                    format!("option `{}` is outside of any remote", key),
+                )),
+            },
+            ParsedLine::Invalid(message) => {
+                // a broken header must not let its options leak into the previous remote
+                if line.trim_start().starts_with('[') {
+                    current = None;
+                }
+                errors.push(ConfigError::new(remote, Some(line_no), message));
+            }
+        }
+    }
+
+    (sections, errors)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '`'] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LineKind {
+    Other,
+    Comment,
+    Section(String),
+    /// A header `parse_config` can't read, its options belong to no remote.
+    BrokenSection,
+    Option(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DocumentLine {
+    /// The line exactly as read, including its line ending.
+    raw: String,
+    kind: LineKind,
+}
+
+impl DocumentLine {
+    fn new(raw: String) -> Self {
+        let text = raw.trim_start_matches('\u{feff}');
+        let kind = match parse_line(text) {
+            ParsedLine::Section(name) => LineKind::Section(name.to_owned()),
+            ParsedLine::Option(key, _) => LineKind::Option(key.to_owned()),
+            ParsedLine::Empty if !text.trim().is_empty() => LineKind::Comment,
+            ParsedLine::Invalid(_) if text.trim_start().starts_with('[') => LineKind::BrokenSection,
+            ParsedLine::Empty | ParsedLine::Invalid(_) => LineKind::Other,
+        };
+
+        Self { raw, kind }
+    }
+
+    fn line_ending(&self) -> &str {
+        if self.raw.ends_with("\r\n") {
+            "\r\n"
+        } else if self.raw.ends_with('\n') {
+            "\n"
+        } else {
+            ""
+        }
+    }
+}
+
+/// Editable rclone.conf that keeps comments, blank lines, unknown keys and line endings
+/// untouched, so only the lines an edit is about ever change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDocument {
+    lines: Vec<DocumentLine>,
+}
+
+impl ConfigDocument {
+    pub fn parse(content: &str) -> Self {
+        Self {
+            lines: content
+                .split_inclusive('\n')
+                .map(|raw| DocumentLine::new(raw.to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn section_names(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match &line.kind {
+                LineKind::Section(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn has_section(&self, name: &str) -> bool {
+        !self.section_ranges(name).is_empty()
+    }
+
+    /// Sets `key = value` in place when the key exists, otherwise appends it to the section.
+    /// Like `parse_config`, repeated sections count as one and the last value of a key wins,
+    /// so earlier copies of the key are removed.
+    pub fn set_option(&mut self, section: &str, key: &str, value: &str) -> bool {
+        let ranges = self.section_ranges(section);
+        let (start, end) = match ranges.last() {
+            Some(&range) => range,
+            None => return false,
+        };
+
+        let mut existing = self.option_lines(&ranges, key);
+
+        match existing.pop() {
+            Some(idx) => {
+                let line_ending = self.lines[idx].line_ending().to_owned();
+                self.lines[idx] = DocumentLine::new(format!("{} = {}{}", key, value, line_ending));
+                for idx in existing.into_iter().rev() {
+                    self.lines.remove(idx);
+                }
+            }
+            None => {
+                let after = (start..end)
+                    .rev()
+                    .find(|&idx| matches!(self.lines[idx].kind, LineKind::Option(_)))
+                    .unwrap_or(start);
+                // the new line takes over the previous line's ending, so a file without a
+                // trailing newline still has none afterwards
+                let line_ending = self.lines[after].line_ending().to_owned();
+                if line_ending.is_empty() {
+                    let default_line_ending = self.default_line_ending();
+                    self.lines[after].raw.push_str(&default_line_ending);
+                }
+
+                self.lines.insert(
+                    after + 1,
+                    DocumentLine::new(format!("{} = {}{}", key, value, line_ending)),
+                );
+            }
+        }
+
+        true
+    }
+
+    /// Removes the key from every section with that name.
+    pub fn remove_option(&mut self, section: &str, key: &str) -> bool {
+        let ranges = self.section_ranges(section);
+        let existing = self.option_lines(&ranges, key);
+
+        for &idx in existing.iter().rev() {
+            self.lines.remove(idx);
+        }
+
+        !existing.is_empty()
+    }
+
+    /// Renames every header of the section, keeping a byte order mark in front of the first.
+    pub fn rename_section(&mut self, old_name: &str, new_name: &str) -> bool {
+        if self.has_section(new_name) {
+            return false;
+        }
+
+        let ranges = self.section_ranges(old_name);
+        for &(start, _) in &ranges {
+            let line = &self.lines[start];
+            let bom = if line.raw.starts_with('\u{feff}') {
+                "\u{feff}"
+            } else {
+                ""
+            };
+            let header = format!("{}[{}]{}", bom, new_name, line.line_ending());
+            self.lines[start] = DocumentLine::new(header);
+        }
+
+        !ranges.is_empty()
+    }
+
+    /// Appends the section's options under `new_name` at the end of the file, its comments
+    /// stay with the original.
+    pub fn copy_section(&mut self, name: &str, new_name: &str) -> bool {
+        if self.has_section(new_name) {
+            return false;
+        }
+        let ranges = self.section_ranges(name);
+        if ranges.is_empty() {
+            return false;
+        }
+
+        let line_ending = self.default_line_ending();
+        let options: Vec<String> = ranges
+            .iter()
+            .flat_map(|&(start, end)| &self.lines[start + 1..end])
+            .filter(|line| matches!(line.kind, LineKind::Option(_)))
+            .map(|line| line.raw.trim_end_matches(['\r', '\n']).to_owned())
+            .collect();
+
+        if let Some(last) = self.lines.last_mut() {
+            if last.line_ending().is_empty() {
+                last.raw.push_str(&line_ending);
+            }
+            if !last.raw.trim().is_empty() {
+                self.lines.push(DocumentLine::new(line_ending.clone()));
+            }
+        }
+        self.lines
+            .push(DocumentLine::new(format!("[{}]{}", new_name, line_ending)));
+        for option in options {
+            self.lines
+                .push(DocumentLine::new(format!("{}{}", option, line_ending)));
+        }
+
+        true
+    }
+
+    /// Removes the header, its options and the blank lines after them, for every section
+    /// with that name. Comments written right above the next header are kept since they
+    /// describe that remote.
+    pub fn delete_section(&mut self, name: &str) -> bool {
+        let ranges = self.section_ranges(name);
+
+        for &(start, mut end) in ranges.iter().rev() {
+            if end < self.lines.len() {
+                while end > start + 1 && self.lines[end - 1].kind == LineKind::Comment {
+                    end -= 1;
+                }
+            }
+            self.lines.drain(start..end);
+        }
+
+        !ranges.is_empty()
+    }
+
+    /// Returns the header index and the index right after the last line of each section
+    /// with that name, in file order.
+    fn section_ranges(&self, name: &str) -> Vec<(usize, usize)> {
+        let header = LineKind::Section(name.to_owned());
+        let mut ranges = Vec::new();
+
+        for (start, line) in self.lines.iter().enumerate() {
+            if line.kind != header {
+                continue;
+            }
+            let end = self.lines[start + 1..]
+                .iter()
+                .position(|line| {
+                    matches!(line.kind, LineKind::Section(_) | LineKind::BrokenSection)
+                })
+                .map(|pos| start + 1 + pos)
+                .unwrap_or(self.lines.len());
+            ranges.push((start, end));
+        }
+
+        ranges
+    }
+
+    /// Indexes of the key's lines within the given sections, in file order.
+    fn option_lines(&self, ranges: &[(usize, usize)], key: &str) -> Vec<usize> {
+        let option = LineKind::Option(key.to_owned());
+        ranges
+            .iter()
+            .flat_map(|&(start, end)| start + 1..end)
+            .filter(|&idx| self.lines[idx].kind == option)
+            .collect()
+    }
+
+    fn default_line_ending(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.line_ending())
+            .find(|ending| !ending.is_empty())
+            .unwrap_or("\n")
+            .to_owned()
+    }
+}
+
+impl fmt::Display for ConfigDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            f.write_str(&line.raw)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(section: &ConfigSection) -> Vec<(&str, &str)> {
+        section
+            .options
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_skips_comments_and_blank_lines() {
+        let (sections, errors) =
+            parse_config("# remotes\n; more\n\n[a]\n  # inside\ntype = s3\n\n");
+        assert!(errors.is_empty());
+        assert_eq!(sections.len(), 1);
+        assert_eq!(options(&sections[0]), [("type", "s3")]);
+    }
+
+    #[test]
+    fn parse_keeps_everything_after_the_first_equals() {
+        let (sections, errors) =
+            parse_config("[a]\npassword = abc==\nfilter=a=b\nempty =\nquoted = \"x y\"\n");
+        assert!(errors.is_empty());
+        assert_eq!(
+            options(&sections[0]),
+            [
+                ("password", "abc=="),
+                ("filter", "a=b"),
+                ("empty", ""),
+                ("quoted", "x y")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_handles_line_endings_and_bom() {
+        for content in [
+            "[a]\ntype = s3",
+            "[a]\r\ntype = s3\r\n",
+            "\u{feff}[a]\ntype = s3\n",
+        ] {
+            let (sections, errors) = parse_config(content);
+            assert!(errors.is_empty(), "{:?}", content);
+            assert_eq!(sections[0].name, "a");
+            assert_eq!(options(&sections[0]), [("type", "s3")]);
+        }
+    }
+
+    #[test]
+    fn parse_keeps_blank_sections_and_merges_repeated_ones() {
+        let (sections, errors) =
+            parse_config("[a]\ntype = s3\n[empty]\n\n[b]\ntype = drive\n[a]\ntype = b2\n");
+        assert!(errors.is_empty());
+
+        let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["a", "empty", "b"]);
+        assert!(sections[1].options.is_empty());
+        assert_eq!(options(&sections[0]), [("type", "s3"), ("type", "b2")]);
+        assert_eq!(sections[0].get("type"), Some("b2"));
+    }
+
+    #[test]
+    fn parse_reports_errors_per_remote() {
+        let content = "key = v\n[a]\ntype = s3\nbroken\n[b\nkey = v\n[]\n[c]\ntype = drive\n";
+        let (sections, errors) = parse_config(content);
+
+        let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["a", "c"]);
+        assert_eq!(options(&sections[0]), [("type", "s3")]);
+
+        let located: Vec<(Option<&str>, Option<usize>)> = errors
+            .iter()
+            .map(|error| (error.remote.as_deref(), error.line))
+            .collect();
+        assert_eq!(
+            located,
+            [
+                (None, Some(1)),
+                (Some("a"), Some(4)),
+                (Some("a"), Some(5)),
+                (None, Some(6)),
+                (None, Some(7)),
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "[a] line 4: expected `key = value`, got `broken`"
+        );
+    }
+
+    fn edited(content: &str, edit: impl FnOnce(&mut ConfigDocument) -> bool) -> String {
+        let mut document = ConfigDocument::parse(content);
+        assert!(edit(&mut document));
+        document.to_string()
+    }
+
+    #[test]
+    fn document_round_trips_byte_for_byte() {
+        let contents = [
+            "",
+            "[a]\ntype = s3\n",
+            "[a]\r\ntype = s3\r\n\r\n[b]\r\ntype = drive\r\n",
+            "[a]\ntype = s3",
+            "[a]\ntype = crypt\npassword = abc==\nfilename_encoding = base64=\n",
+            "\u{feff}# remotes\n; more\n[a]\n  type=s3  \n\n\n[broken\nkey\n",
+            "[a]\ntype = s3\n[a]\nregion = eu\n",
+        ];
+
+        for content in contents {
+            assert_eq!(ConfigDocument::parse(content).to_string(), content);
+        }
+    }
+
+    #[test]
+    fn set_option_replaces_in_place() {
+        let content = "[a]\n# region\nregion = us\ntype = s3\n";
+        assert_eq!(
+            edited(content, |d| d.set_option("a", "region", "eu")),
+            "[a]\n# region\nregion = eu\ntype = s3\n"
+        );
+    }
+
+    #[test]
+    fn set_option_appends_after_the_last_option() {
+        assert_eq!(
+            edited("[a]\ntype = s3\n\n[b]\ntype = drive\n", |d| d
+                .set_option("a", "region", "eu")),
+            "[a]\ntype = s3\nregion = eu\n\n[b]\ntype = drive\n"
+        );
+        assert_eq!(
+            edited("[a]\r\ntype = s3\r\n", |d| d
+                .set_option("a", "region", "eu")),
+            "[a]\r\ntype = s3\r\nregion = eu\r\n"
+        );
+        assert_eq!(
+            edited("[a]\ntype = s3", |d| d.set_option("a", "region", "eu")),
+            "[a]\ntype = s3\nregion = eu"
+        );
+    }
+
+    #[test]
+    fn set_option_handles_repeated_sections() {
+        let content = "[a]\ntype = s3\nregion = us\n\n[b]\ntype = drive\n\n[a]\nregion = ca\n";
+        let result = edited(content, |d| d.set_option("a", "region", "eu"));
+        assert_eq!(
+            result,
+            "[a]\ntype = s3\n\n[b]\ntype = drive\n\n[a]\nregion = eu\n"
+        );
+
+        let (sections, _) = parse_config(&result);
+        assert_eq!(sections[0].get("region"), Some("eu"));
+
+        assert_eq!(
+            edited("[a]\ntype = s3\n[a]\nacl = private\n", |d| d
+                .set_option("a", "region", "eu")),
+            "[a]\ntype = s3\n[a]\nacl = private\nregion = eu\n"
+        );
+    }
+
+    #[test]
+    fn set_option_needs_the_section() {
+        let mut document = ConfigDocument::parse("[a]\ntype = s3\n");
+        assert!(!document.set_option("b", "region", "eu"));
+        assert_eq!(document.to_string(), "[a]\ntype = s3\n");
+    }
+
+    #[test]
+    fn remove_option_removes_every_copy() {
+        let content = "[a]\ntype = s3\nregion = us\n[b]\nregion = ca\n[a]\nregion = eu\n";
+        assert_eq!(
+            edited(content, |d| d.remove_option("a", "region")),
+            "[a]\ntype = s3\n[b]\nregion = ca\n[a]\n"
+        );
+
+        let mut document = ConfigDocument::parse(content);
+        assert!(!document.remove_option("a", "acl"));
+        assert!(!document.remove_option("c", "region"));
+    }
+
+    #[test]
+    fn broken_headers_end_the_section_before() {
+        let content = "[a]\ntype = s3\n[b\nregion = us\n";
+
+        let mut document = ConfigDocument::parse(content);
+        assert!(!document.remove_option("a", "region"));
+        assert_eq!(document.to_string(), content);
+
+        assert_eq!(
+            edited(content, |d| d.set_option("a", "region", "eu")),
+            "[a]\ntype = s3\nregion = eu\n[b\nregion = us\n"
+        );
+        assert_eq!(
+            edited(content, |d| d.copy_section("a", "c")),
+            "[a]\ntype = s3\n[b\nregion = us\n\n[c]\ntype = s3\n"
+        );
+        assert_eq!(
+            edited(content, |d| d.delete_section("a")),
+            "[b\nregion = us\n"
+        );
+    }
+
+    #[test]
+    fn rename_section_keeps_the_rest() {
+        assert_eq!(
+            edited("\u{feff}[a]\r\ntype = s3\r\n", |d| d
+                .rename_section("a", "b")),
+            "\u{feff}[b]\r\ntype = s3\r\n"
+        );
+        assert_eq!(
+            edited("[a]\ntype = s3\n[a]\nregion = eu", |d| d
+                .rename_section("a", "b")),
+            "[b]\ntype = s3\n[b]\nregion = eu"
+        );
+
+        let mut document = ConfigDocument::parse("[a]\ntype = s3\n[b]\ntype = drive\n");
+        assert!(!document.rename_section("a", "b"));
+        assert!(!document.rename_section("c", "d"));
+    }
+
+    #[test]
+    fn copy_section_appends_the_options() {
+        assert_eq!(
+            edited("[a]\n# keys\ntype = s3\nregion = eu\n", |d| d
+                .copy_section("a", "b")),
+            "[a]\n# keys\ntype = s3\nregion = eu\n\n[b]\ntype = s3\nregion = eu\n"
+        );
+        assert_eq!(
+            edited("[a]\r\ntype = s3", |d| d.copy_section("a", "b")),
+            "[a]\r\ntype = s3\r\n\r\n[b]\r\ntype = s3\r\n"
+        );
+        assert_eq!(
+            edited("[a]\ntype = s3\n[a]\nregion = eu\n", |d| d
+                .copy_section("a", "b")),
+            "[a]\ntype = s3\n[a]\nregion = eu\n\n[b]\ntype = s3\nregion = eu\n"
+        );
+
+        let mut document = ConfigDocument::parse("[a]\ntype = s3\n[b]\ntype = drive\n");
+        assert!(!document.copy_section("a", "b"));
+        assert!(!document.copy_section("c", "d"));
+    }
+
+    #[test]
+    fn delete_section_keeps_the_next_remotes_comments() {
+        assert_eq!(
+            edited("[a]\ntype = s3\n\n# work\n[b]\ntype = drive\n", |d| d
+                .delete_section("a")),
+            "# work\n[b]\ntype = drive\n"
+        );
+        assert_eq!(
+            edited("[b]\ntype = drive\n\n[a]\ntype = s3\n# trailing\n", |d| d
+                .delete_section("a")),
+            "[b]\ntype = drive\n\n"
+        );
+        assert_eq!(
+            edited(
+                "[a]\ntype = s3\n[b]\ntype = drive\n[a]\nregion = eu\n",
+                |d| d.delete_section("a")
+            ),
+            "[b]\ntype = drive\n"
+        );
+
+        let mut document = ConfigDocument::parse("[a]\ntype = s3\n");
+        assert!(!document.delete_section("b"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    collections::BTreeMap,
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use super::providers::ProviderOption;
+use crate::utilities::utils::{output_cancellable, rclone_command};
+
+/// How long one config call may run, long enough to sign in through the browser.
+pub const CONFIG_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Where rclone's non-interactive config protocol left off.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigStep {
+    /// rclone needs `option` answered, passing `state` back with the answer.
+    Question {
+        state: String,
+        option: Box<ProviderOption>,
+        error: String,
+    },
+    Done,
+}
+
+/// A config call running in the background. Cancelling kills the rclone child, which
+/// also frees the port its OAuth web server listens on.
+#[derive(Debug, Clone)]
+pub struct ConfigJob {
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+}
+
+impl Default for ConfigJob {
+    fn default() -> Self {
+        Self {
+            cancel: Arc::new(AtomicBool::new(false)),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl ConfigJob {
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn cancel_flag(&self) -> &AtomicBool {
+        &self.cancel
+    }
+}
+
+/// The JSON rclone prints for every `--non-interactive` config call.
+#[derive(Debug, Deserialize)]
+struct ConfigOut {
+    #[serde(rename = "State", default)]
+    state: String,
+
+    #[serde(rename = "Option", default)]
+    option: Option<ProviderOption>,
+
+    #[serde(rename = "Error", default)]
+    error: String,
+}
+
+/// Creates the remote with the options already known, passwords among them must already
+/// be obscured. `ask_all` makes rclone ask for every option instead of only the ones its
+/// config flow needs, used when the provider's schema is unknown.
+pub fn start_config(
+    name: &str,
+    provider: &str,
+    options: &BTreeMap<String, String>,
+    ask_all: bool,
+    job: &ConfigJob,
+) -> Result<ConfigStep, String> {
+    let mut cmd = rclone_command();
+    cmd.args(["config", "create", name, provider]);
+    for (key, value) in options {
+        cmd.arg(key).arg(value);
+    }
+    cmd.args(["--non-interactive", "--no-obscure"]);
+    if ask_all {
+        cmd.arg("--all");
+    }
+
+    run_step(cmd, job)
+}
+
+/// Answers the question asked in `state`.
+pub fn continue_config(
+    name: &str,
+    state: &str,
+    result: &str,
+    job: &ConfigJob,
+) -> Result<ConfigStep, String> {
+    let mut cmd = rclone_command();
+    cmd.args([
+        "config",
+        "update",
+        name,
+        "--continue",
+        "--state",
+        state,
+        "--result",
+        result,
+        "--non-interactive",
+    ]);
+
+    run_step(cmd, job)
+}
+
+fn run_step(mut cmd: Command, job: &ConfigJob) -> Result<ConfigStep, String> {
+    let output = output_cancellable(&mut cmd, CONFIG_TIMEOUT, job.cancel_flag())?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+    if !output.status.success() {
+        return Err(match stderr.is_empty() {
+            true => format!("rclone exited with {}", output.status),
+            false => stderr,
+        });
+    }
+    if !stderr.is_empty() {
+        tracing::info!("rclone config: {}", stderr);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(ConfigStep::Done);
+    }
+
+    let out: ConfigOut = serde_json::from_str(stdout.trim())
+        .map_err(|err| format!("invalid rclone config output: {}", err))?;
+
+    match out.option {
+        Some(option) if !out.state.is_empty() => Ok(ConfigStep::Question {
+            state: out.state,
+            option: Box::new(option),
+            error: out.error,
+        }),
+        _ if !out.error.is_empty() => Err(out.error),
+        _ => Ok(ConfigStep::Done),
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::bundle::BundleRemote;
+use crate::utilities::utils::{app_config_path, write_private_file};
+
+/// Storages deleted from the Manage tab, kept with their secrets in `trash.json` next to
+/// DriveFUSE's config so a deletion can be undone. Not used when rclone.conf is encrypted,
+/// since the trash would hold those secrets unencrypted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trash {
+    pub entries: Vec<TrashEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub remote: BundleRemote,
+    pub deleted_at: String,
+}
+
+impl Trash {
+    /// Oldest entries are dropped beyond this.
+    const MAX_ENTRIES: usize = 20;
+
+    fn path() -> Option<PathBuf> {
+        app_config_path().map(|path| path.join("trash.json"))
+    }
+
+    pub fn load() -> Self {
+        let content = match Self::path().map(fs::read_to_string) {
+            Some(Ok(content)) => content,
+            _ => return Self::default(),
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|err| {
+            tracing::error!("Error reading trash: {}", err);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("Failed to get config path")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+
+        let json = serde_json::to_string_pretty(self).expect("Failed to serialize trash");
+        // tokens are in there
+        write_private_file(&path, json.as_bytes()).map_err(|err| err.to_string())
+    }
+
+    /// Saved before rclone deletes anything, so a failed write stops the deletion.
+    pub fn push(&mut self, remote: BundleRemote) -> Result<(), String> {
+        let mut trash = self.clone();
+        trash.entries.push(TrashEntry {
+            remote,
+            deleted_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        });
+        if trash.entries.len() > Self::MAX_ENTRIES {
+            let excess = trash.entries.len() - Self::MAX_ENTRIES;
+            trash.entries.drain(..excess);
+        }
+
+        trash.save()?;
+        *self = trash;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<TrashEntry, String> {
+        if index >= self.entries.len() {
+            return Err("The storage is no longer in the trash".to_owned());
+        }
+
+        let entry = self.entries.remove(index);
+        self.save()?;
+        Ok(entry)
+    }
+
+    /// Drops the newest entry, for a deletion that failed after it was pushed.
+    pub fn pop(&mut self) -> Result<(), String> {
+        match self.entries.len() {
+            0 => Ok(()),
+            len => self.remove(len - 1).map(|_| ()),
+        }
+    }
+}
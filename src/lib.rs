@@ -9,14 +9,25 @@ pub mod utilities {
 pub use app::DriveFUSE;
 pub mod backend {
     pub mod app_config;
+    pub mod bundle;
     pub mod mounting;
     pub mod mounting_options;
+    pub mod providers;
     pub mod rclone;
+    pub mod rclone_conf;
+    pub mod remote_config;
+    pub mod trash;
 }
 pub mod ui {
+    pub mod bundle;
+    pub mod create_wizard;
+    pub mod drive_options;
     pub mod manage;
     pub mod mount_unmount;
+    pub mod option_editor;
+    pub mod remote_forms;
     pub mod settings;
     pub mod top_panel;
+    pub mod unlock_config;
 }
 pub mod error_app;
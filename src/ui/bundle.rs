@@ -0,0 +1,313 @@
+use std::collections::HashSet;
+
+use egui::{Button, Color32, ComboBox, Grid, RichText, TextEdit, Ui};
+
+use crate::{
+    backend::bundle::{Bundle, BundleFormat, ImportAction},
+    utilities::{
+        enums::Message,
+        utils::{validate_remote_name, write_private_file},
+    },
+    DriveFUSE,
+};
+
+/// Selections of the export form and the bundle waiting to be imported.
+#[derive(Debug, Default)]
+pub struct BundleState {
+    selected: HashSet<String>,
+    include_settings: bool,
+    include_secrets: bool,
+    format: BundleFormat,
+
+    loaded: Option<Bundle>,
+    actions: Vec<ImportAction>,
+    importing: bool,
+
+    pub status: Option<Result<String, String>>,
+}
+
+impl BundleState {
+    /// Remotes that already exist are skipped unless the user picks otherwise.
+    pub fn load(&mut self, bundle: Bundle, existing: &[String]) {
+        self.actions = bundle
+            .remotes
+            .iter()
+            .map(|remote| {
+                if existing.contains(&remote.name) {
+                    ImportAction::Skip
+                } else {
+                    ImportAction::Import
+                }
+            })
+            .collect();
+        self.loaded = Some(bundle);
+        self.status = None;
+    }
+
+    pub fn finish_import(&mut self, imported: usize, errors: Vec<String>) {
+        self.importing = false;
+        if errors.is_empty() {
+            self.loaded = None;
+            self.actions.clear();
+            self.status = Some(Ok(format!("Imported {} remote(s)", imported)));
+        } else {
+            self.status = Some(Err(errors.join("\n")));
+        }
+    }
+}
+
+fn action_name(action: &ImportAction) -> &str {
+    match action {
+        ImportAction::Import => "Import",
+        ImportAction::Rename(_) => "Rename",
+        ImportAction::Skip => "Skip",
+        ImportAction::Overwrite => "Overwrite",
+    }
+}
+
+/// Name the remote ends up with, `None` when it is skipped.
+fn target_name<'a>(name: &'a str, action: &'a ImportAction) -> Option<&'a str> {
+    match action {
+        ImportAction::Import | ImportAction::Overwrite => Some(name),
+        ImportAction::Rename(new_name) => Some(new_name.as_str()),
+        ImportAction::Skip => None,
+    }
+}
+
+pub fn render_bundle(ui: &mut Ui, app: &mut DriveFUSE) {
+    let ctx = ui.ctx().clone();
+    let existing: Vec<String> = app.rclone.storages.iter().map(|s| s.name.clone()).collect();
+
+    // * Export
+    ui.label(RichText::new("Export").strong());
+
+    ui.horizontal_wrapped(|ui| {
+        for name in &existing {
+            let mut checked = app.bundle.selected.contains(name);
+            if ui.checkbox(&mut checked, name).changed() {
+                if checked {
+                    app.bundle.selected.insert(name.clone());
+                } else {
+                    app.bundle.selected.remove(name);
+                }
+            }
+        }
+    });
+    app.bundle.selected.retain(|name| existing.contains(name));
+
+    let supports_settings = app.bundle.format == BundleFormat::Json;
+    ui.add_enabled_ui(supports_settings, |ui| {
+        let mut include_settings = app.bundle.include_settings && supports_settings;
+        ui.checkbox(
+            &mut include_settings,
+            "Include drive letters and auto mount",
+        )
+        .on_disabled_hover_text("Only JSON bundles can hold DriveFUSE's own settings");
+        if supports_settings {
+            app.bundle.include_settings = include_settings;
+        }
+    });
+    ui.checkbox(
+        &mut app.bundle.include_secrets,
+        "Include passwords and tokens",
+    );
+    if app.bundle.include_secrets {
+        ui.label(
+            RichText::new("Anyone with the file gets access to these storages")
+                .color(Color32::YELLOW),
+        );
+    }
+
+    ui.horizontal(|ui| {
+        ComboBox::from_id_source("bundle_format")
+            .selected_text(app.bundle.format.name())
+            .show_ui(ui, |ui| {
+                for format in BundleFormat::values() {
+                    ui.selectable_value(&mut app.bundle.format, format, format.name());
+                }
+            });
+
+        if ui
+            .add_enabled(!app.bundle.selected.is_empty(), Button::new("Export"))
+            .clicked()
+        {
+            let bundle = Bundle::export(
+                &app.rclone.storages,
+                &app.bundle.selected,
+                app.bundle.include_secrets,
+                (app.bundle.include_settings && supports_settings).then_some(&app.app_config),
+            );
+            let format = app.bundle.format;
+            let content = bundle.to_content(format);
+            let include_secrets = app.bundle.include_secrets;
+
+            tokio::spawn(async move {
+                let res = rfd::AsyncFileDialog::new()
+                    .add_filter(format.name(), &[format.extension()])
+                    .set_file_name(format!("drive_fuse_remotes.{}", format.extension()))
+                    .save_file()
+                    .await;
+
+                if let Some(file) = res {
+                    let result = if include_secrets {
+                        write_private_file(file.path(), content.as_bytes())
+                    } else {
+                        std::fs::write(file.path(), content)
+                    };
+                    if let Err(err) = result {
+                        tracing::error!("Failed to write bundle: {}", err);
+                    }
+                }
+            });
+        }
+    });
+
+    ui.add_space(8.0);
+
+    // * Import
+    ui.label(RichText::new("Import").strong());
+
+    if ui.button("Open bundle").clicked() {
+        let tx = app.tx_egui.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let res = rfd::AsyncFileDialog::new()
+                .add_filter("Bundle", &["json", "conf"])
+                .pick_file()
+                .await;
+
+            if let Some(file) = res {
+                let bundle = std::fs::read_to_string(file.path())
+                    .map_err(|err| format!("Failed to read bundle: {}", err))
+                    .and_then(|content| Bundle::parse(&content));
+                tx.send(Message::BundleLoaded(bundle))
+                    .expect("Failed to send BundleLoaded message");
+                ctx.request_repaint();
+            }
+        });
+    }
+
+    if let Some(bundle) = app.bundle.loaded.clone() {
+        let mut targets: Vec<&str> = vec![];
+        let mut conflicts: HashSet<String> = HashSet::new();
+
+        Grid::new("bundle_import_grid")
+            .num_columns(3)
+            .spacing([8.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for (remote, action) in bundle.remotes.iter().zip(app.bundle.actions.iter_mut()) {
+                    ui.label(&remote.name);
+
+                    let exists = existing.contains(&remote.name);
+                    ComboBox::from_id_source(("bundle_action", &remote.name))
+                        .selected_text(action_name(action))
+                        .show_ui(ui, |ui| {
+                            ui.add_enabled_ui(!exists, |ui| {
+                                ui.selectable_value(action, ImportAction::Import, "Import");
+                            });
+                            if ui
+                                .selectable_label(
+                                    matches!(action, ImportAction::Rename(_)),
+                                    "Rename",
+                                )
+                                .clicked()
+                                && !matches!(action, ImportAction::Rename(_))
+                            {
+                                *action = ImportAction::Rename(format!("{}_imported", remote.name));
+                            }
+                            ui.selectable_value(action, ImportAction::Skip, "Skip");
+                            ui.add_enabled_ui(exists, |ui| {
+                                ui.selectable_value(action, ImportAction::Overwrite, "Overwrite")
+                                    .on_hover_text("Replaces the existing remote");
+                            });
+                        });
+
+                    if let ImportAction::Rename(new_name) = action {
+                        ui.add(TextEdit::singleline(new_name).desired_width(160.0));
+                    } else if exists {
+                        ui.label(RichText::new("already exists").color(Color32::YELLOW));
+                    } else {
+                        ui.label("");
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+        for (remote, action) in bundle.remotes.iter().zip(&app.bundle.actions) {
+            if let Some(target) = target_name(&remote.name, action) {
+                let taken = match action {
+                    ImportAction::Overwrite => false,
+                    _ => existing.iter().any(|name| name == target),
+                };
+                if validate_remote_name(target).is_err() || taken || targets.contains(&target) {
+                    conflicts.insert(remote.name.clone());
+                }
+                targets.push(target);
+            }
+        }
+
+        for name in &conflicts {
+            ui.label(
+                RichText::new(format!("{}: the name is invalid or already used", name))
+                    .color(Color32::RED),
+            );
+        }
+
+        let can_apply = conflicts.is_empty() && !targets.is_empty() && !app.bundle.importing;
+        ui.horizontal(|ui| {
+            if ui.add_enabled(can_apply, Button::new("Apply")).clicked() {
+                let actions = app.bundle.actions.clone();
+
+                // the import runs where the mounts can't be reached, so overwritten remotes
+                // are unmounted here
+                let unmount_error = bundle
+                    .remotes
+                    .iter()
+                    .zip(&actions)
+                    .filter(|(_, action)| **action == ImportAction::Overwrite)
+                    .find_map(|(remote, _)| app.unmount_for_edit(&remote.name).err());
+
+                if let Some(err) = unmount_error {
+                    app.bundle.status = Some(Err(err));
+                } else {
+                    app.bundle.importing = true;
+                    app.bundle.status = None;
+
+                    let storages = app.rclone.storages.clone();
+                    let tx = app.tx_egui.clone();
+                    let ctx = ctx.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let (imported, errors) = bundle.import(&actions, &storages);
+                        tx.send(Message::BundleImported { imported, errors })
+                            .expect("Failed to send BundleImported message");
+                        ctx.request_repaint();
+                    });
+                }
+            }
+
+            if ui
+                .add_enabled(!app.bundle.importing, Button::new("Cancel"))
+                .clicked()
+            {
+                app.bundle.loaded = None;
+                app.bundle.actions.clear();
+            }
+
+            if app.bundle.importing {
+                ui.spinner();
+            }
+        });
+    }
+
+    match &app.bundle.status {
+        Some(Ok(status)) => {
+            ui.label(status);
+        }
+        Some(Err(error)) => {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,624 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use egui::{
+    Align2, Button, Color32, ComboBox, Context, Grid, RichText, ScrollArea, TextEdit, Ui, Window,
+};
+
+use crate::{
+    backend::{
+        providers::{Provider, ProviderOption},
+        rclone::{Rclone, TokenStruct},
+        remote_config::{continue_config, start_config, ConfigJob, ConfigStep, CONFIG_TIMEOUT},
+    },
+    ui::remote_forms::{
+        form_options, form_passwords, prepare_options, render_remote_form, Upstream,
+    },
+    utilities::{enums::Message, utils::write_private_file},
+    DriveFUSE,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WizardPage {
+    /// The provider's options, filled in before rclone is started.
+    Options,
+    /// A question asked by rclone's config flow.
+    Question {
+        state: String,
+        option: Box<ProviderOption>,
+        error: String,
+    },
+    /// Waiting for rclone.
+    Running,
+    /// The remote is saved but its secrets can't be recovered, so the user has to keep
+    /// them before the wizard closes.
+    Acknowledge,
+}
+
+/// State of the "Add storage" wizard.
+#[derive(Debug)]
+pub struct CreateWizard {
+    pub name: String,
+    pub provider: Provider,
+    pub values: BTreeMap<String, String>,
+    show_advanced: bool,
+    page: WizardPage,
+    answer: String,
+    /// rclone wrote the remote to the config, so cancelling has to remove it.
+    is_created: bool,
+    /// Authorize OAuth on another machine through `rclone authorize`.
+    remote_auth: bool,
+    /// Outcome of the last connection test of a provider form.
+    pub test: Option<Result<String, String>>,
+    pub is_testing: bool,
+    /// Folders or buckets listed by a provider form, to pick the mount root from.
+    pub buckets: Option<Vec<String>>,
+    pub is_listing: bool,
+    pub mount_root: Option<String>,
+    pub secrets_exported: bool,
+    secrets_acknowledged: bool,
+    /// The rclone config call the Running page waits for.
+    job: Option<ConfigJob>,
+    /// Remotes offered to a union or combine form.
+    pub upstreams: Vec<Upstream>,
+    pub error: Option<String>,
+}
+
+impl CreateWizard {
+    pub fn new(name: String, provider: Provider) -> Self {
+        Self {
+            name,
+            provider,
+            values: BTreeMap::new(),
+            show_advanced: false,
+            page: WizardPage::Options,
+            answer: String::new(),
+            is_created: false,
+            // no browser to sign in with when running over SSH
+            remote_auth: std::env::var_os("SSH_CONNECTION").is_some(),
+            test: None,
+            is_testing: false,
+            buckets: None,
+            is_listing: false,
+            mount_root: None,
+            secrets_exported: false,
+            secrets_acknowledged: false,
+            job: None,
+            upstreams: vec![],
+            error: None,
+        }
+    }
+
+    /// Moves to whatever rclone answered with, `true` once the remote is done.
+    pub fn next_step(&mut self, step: ConfigStep) -> bool {
+        self.job = None;
+        match step {
+            ConfigStep::Done if self.provider.prefix == "crypt" => {
+                self.is_created = true;
+                self.page = WizardPage::Acknowledge;
+            }
+            ConfigStep::Done => return true,
+            ConfigStep::Question {
+                state,
+                option,
+                error,
+            } => {
+                self.is_created = true;
+                self.answer = option.default_string();
+                self.error = None;
+                self.page = WizardPage::Question {
+                    state,
+                    option,
+                    error,
+                };
+            }
+        }
+
+        false
+    }
+
+    pub fn fail(&mut self, error: String) {
+        self.job = None;
+        self.error = Some(error);
+        if !self.is_created {
+            self.page = WizardPage::Options;
+        }
+    }
+
+    pub fn finish_test(&mut self, result: Result<String, String>) {
+        self.is_testing = false;
+        self.test = Some(result);
+    }
+
+    pub fn finish_listing(&mut self, result: Result<Vec<String>, String>) {
+        self.is_listing = false;
+        match result {
+            Ok(buckets) => self.buckets = Some(buckets),
+            Err(err) => self.test = Some(Err(err)),
+        }
+    }
+
+    /// Keys whose values are obscured before they reach rclone.
+    pub fn passwords(&self) -> Vec<String> {
+        self.provider
+            .options
+            .iter()
+            .filter(|option| option.is_password)
+            .map(|option| option.name.clone())
+            .chain(
+                form_passwords(&self.provider.prefix)
+                    .iter()
+                    .map(|key| key.to_string()),
+            )
+            .collect()
+    }
+
+    /// rclone's "use a web browser" question, answered on its own in remote auth mode.
+    fn auto_answer(&mut self) -> Option<(String, String)> {
+        match &self.page {
+            WizardPage::Question { state, option, .. }
+                if self.remote_auth && option.name == "config_is_local" =>
+            {
+                let state = state.clone();
+                self.page = WizardPage::Running;
+                Some((state, "false".to_owned()))
+            }
+            _ => None,
+        }
+    }
+
+    fn uses_oauth(&self) -> bool {
+        self.provider.options.is_empty() || self.provider.uses_oauth()
+    }
+
+    /// The S3 style sub-provider picked so far, options are filtered by it.
+    fn sub_provider(&self) -> &str {
+        self.values
+            .get("provider")
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    fn visible_options(&self) -> Vec<&ProviderOption> {
+        let sub_provider = self.sub_provider();
+        let form = form_options(&self.provider.prefix);
+        let mut options: Vec<&ProviderOption> = self
+            .provider
+            .options
+            .iter()
+            .filter(|option| option.hide == 0 && option.applies_to(sub_provider))
+            .filter(|option| self.show_advanced || !option.advanced)
+            .filter(|option| !form.contains(&option.name.as_str()))
+            .collect();
+        // required options first, the rest in rclone's order
+        options.sort_by_key(|option| !option.required);
+        options
+    }
+}
+
+/// Shows the crypt password and salt until they were exported or the user confirms
+/// keeping them, returns `true` when the wizard may close.
+fn render_acknowledge(ui: &mut Ui, app: &mut DriveFUSE) -> bool {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return true,
+    };
+
+    ui.label(
+        RichText::new("Without the password and salt the encrypted files can never be read again")
+            .color(Color32::YELLOW),
+    );
+    ui.add_space(4.0);
+
+    let password = wizard.values.get("password").cloned().unwrap_or_default();
+    let salt = wizard.values.get("password2").cloned().unwrap_or_default();
+    Grid::new("crypt_secrets_grid")
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Password");
+            ui.monospace(&password);
+            ui.end_row();
+            ui.label("Salt");
+            ui.monospace(if salt.is_empty() {
+                "none"
+            } else {
+                salt.as_str()
+            });
+            ui.end_row();
+        });
+
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        if ui.button("Export to file").clicked() {
+            let name = wizard.name.clone();
+            let content = format!(
+                "rclone crypt remote: {}\nremote = {}\npassword = {}\nsalt = {}\n",
+                wizard.name,
+                wizard.values.get("remote").cloned().unwrap_or_default(),
+                password,
+                salt,
+            );
+            let tx = app.tx_egui.clone();
+            let ctx = ui.ctx().clone();
+            tokio::spawn(async move {
+                let res = rfd::AsyncFileDialog::new()
+                    .set_file_name(format!("{}-crypt-secrets.txt", name))
+                    .save_file()
+                    .await;
+
+                if let Some(file) = res {
+                    match write_private_file(file.path(), content.as_bytes()) {
+                        Ok(()) => {
+                            tx.send(Message::SecretsExported { name })
+                                .expect("Failed to send SecretsExported message");
+                            ctx.request_repaint();
+                        }
+                        Err(err) => tracing::error!("Failed to export crypt secrets: {}", err),
+                    }
+                }
+            });
+        }
+        if wizard.secrets_exported {
+            ui.label(RichText::new("Exported").color(Color32::GREEN));
+        }
+    });
+
+    ui.checkbox(
+        &mut wizard.secrets_acknowledged,
+        "I have stored the password and salt somewhere safe",
+    );
+
+    let can_finish = wizard.secrets_exported || wizard.secrets_acknowledged;
+    ui.add_space(8.0);
+    ui.add_enabled(can_finish, Button::new("Finish")).clicked()
+}
+
+/// Runs one config call as the wizard's job, the Cancel button kills it.
+fn send_step(
+    app: &mut DriveFUSE,
+    ctx: &Context,
+    step: impl FnOnce(&str, &ConfigJob) -> Result<ConfigStep, String> + Send + 'static,
+) {
+    let job = ConfigJob::default();
+    let name = match &mut app.create_wizard {
+        Some(wizard) => {
+            wizard.job = Some(job.clone());
+            wizard.name.clone()
+        }
+        None => return,
+    };
+    let tx = app.tx_egui.clone();
+    let ctx = ctx.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = step(&name, &job);
+
+        // rclone writes the remote before its first call finishes, so a cancelled
+        // wizard cleans up once the child is gone
+        if job.is_cancelled() {
+            if let Err(err) = Rclone::delete_remote(&name) {
+                tracing::error!("Failed to remove {}: {}", name, err);
+            }
+        }
+
+        let message = match result {
+            Ok(ConfigStep::Done) => Message::RemoteCreated { name },
+            Ok(step) => Message::RemoteConfigStep { name, step },
+            Err(error) => {
+                tracing::error!("Error creating {}: {}", name, error);
+                Message::RemoteCreationFailed { name, error }
+            }
+        };
+        tx.send(message)
+            .expect("Failed to send remote creation message");
+        ctx.request_repaint();
+    });
+}
+
+/// The `rclone authorize` command rclone put in the token question, including any
+/// encoded client settings.
+fn authorize_command(help: &str, provider: &str) -> String {
+    help.lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("rclone authorize"))
+        .map(str::to_owned)
+        .unwrap_or_else(|| format!("rclone authorize \"{}\"", provider))
+}
+
+/// Asks for the output of `rclone authorize`, returning the token once it is valid.
+fn token_input(
+    ui: &mut Ui,
+    option: &ProviderOption,
+    value: &mut String,
+    provider: &str,
+) -> Option<String> {
+    let command = authorize_command(&option.help, provider);
+
+    ui.label("Run this on a machine with a web browser and the same rclone version:");
+    ui.horizontal(|ui| {
+        ui.code(&command);
+        if ui.small_button("Copy").clicked() {
+            ui.output_mut(|o| o.copied_text = command.clone());
+        }
+    });
+
+    ui.add_space(4.0);
+    ui.label("Then paste everything it printed:");
+    ui.add(
+        TextEdit::multiline(value)
+            .desired_rows(4)
+            .desired_width(f32::INFINITY)
+            .hint_text("Paste the following into your remote machine --->"),
+    );
+
+    if value.trim().is_empty() {
+        return None;
+    }
+
+    match TokenStruct::from_authorize_output(value) {
+        Ok(token) => Some(token),
+        Err(err) => {
+            ui.label(RichText::new(err).color(Color32::RED));
+            None
+        }
+    }
+}
+
+/// Input matching the option's type and examples.
+fn option_input(ui: &mut Ui, option: &ProviderOption, value: &mut String, sub_provider: &str) {
+    let examples = option.examples_for(sub_provider);
+
+    if option.value_type == "bool" {
+        ComboBox::from_id_source(("option_input", &option.name))
+            .selected_text(if value.is_empty() {
+                "default"
+            } else {
+                value.as_str()
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(value, String::new(), "default");
+                ui.selectable_value(value, "true".to_owned(), "true");
+                ui.selectable_value(value, "false".to_owned(), "false");
+            });
+    } else if option.exclusive && !examples.is_empty() {
+        ComboBox::from_id_source(("option_input", &option.name))
+            .selected_text(value.as_str())
+            .width(220.0)
+            .show_ui(ui, |ui| {
+                for example in &examples {
+                    ui.selectable_value(value, example.value.clone(), &example.value)
+                        .on_hover_text(&example.help);
+                }
+            });
+    } else {
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(value)
+                    .password(option.is_password)
+                    .hint_text(option.default_string())
+                    .desired_width(220.0),
+            );
+
+            if !examples.is_empty() {
+                ui.menu_button("Examples", |ui| {
+                    for example in &examples {
+                        if ui
+                            .button(&example.value)
+                            .on_hover_text(&example.help)
+                            .clicked()
+                        {
+                            *value = example.value.clone();
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+fn render_options(ui: &mut Ui, app: &mut DriveFUSE) -> bool {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return false,
+    };
+
+    if wizard.provider.options.is_empty() && form_options(&wizard.provider.prefix).is_empty() {
+        ui.label("rclone will ask for every option of this provider one at a time.");
+    } else {
+        ui.checkbox(&mut wizard.show_advanced, "Show advanced options");
+    }
+
+    if wizard.uses_oauth() {
+        ui.checkbox(
+            &mut wizard.remote_auth,
+            "Sign in on another machine (no browser here)",
+        )
+        .on_hover_text("Runs `rclone authorize` elsewhere and takes the token it prints");
+    }
+
+    let mut errors = render_remote_form(ui, app);
+
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return false,
+    };
+    let sub_provider = wizard.sub_provider().to_owned();
+    let options: Vec<ProviderOption> = wizard.visible_options().into_iter().cloned().collect();
+
+    ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+        Grid::new("create_wizard_options")
+            .num_columns(2)
+            .spacing([8.0, 6.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for option in &options {
+                    let label = if option.required {
+                        format!("{} *", option.name)
+                    } else {
+                        option.name.clone()
+                    };
+                    ui.label(label).on_hover_text(&option.help);
+
+                    let value = wizard.values.entry(option.name.clone()).or_default();
+                    option_input(ui, option, value, &sub_provider);
+                    if let Err(err) = option.validate(value) {
+                        errors.push(err);
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+
+    for error in &errors {
+        ui.label(RichText::new(error).color(Color32::RED));
+    }
+
+    errors.is_empty()
+}
+
+pub fn render_create_wizard(ctx: &Context, app: &mut DriveFUSE) {
+    if let Some((state, answer)) = app.create_wizard.as_mut().and_then(|w| w.auto_answer()) {
+        send_step(app, ctx, move |name, job| {
+            continue_config(name, &state, &answer, job)
+        });
+    }
+
+    let (name, provider_name, page) = match &app.create_wizard {
+        Some(wizard) => (
+            wizard.name.clone(),
+            wizard.provider.display_name().to_owned(),
+            wizard.page.clone(),
+        ),
+        None => return,
+    };
+
+    let is_acknowledging = page == WizardPage::Acknowledge;
+    let mut cancel = false;
+
+    Window::new(format!("Add {} storage", provider_name))
+        .collapsible(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("Name: {}", name));
+            ui.add_space(8.0);
+
+            match page {
+                WizardPage::Options => {
+                    let is_valid = render_options(ui, app);
+
+                    ui.add_space(8.0);
+                    if ui.add_enabled(is_valid, Button::new("Create")).clicked() {
+                        if let Some(wizard) = &mut app.create_wizard {
+                            let provider = wizard.provider.prefix.clone();
+                            let ask_all = wizard.provider.options.is_empty()
+                                && form_options(&provider).is_empty();
+                            let values = wizard.values.clone();
+                            let passwords = wizard.passwords();
+                            wizard.error = None;
+                            wizard.page = WizardPage::Running;
+
+                            send_step(app, ui.ctx(), move |name, job| {
+                                let options = prepare_options(&values, &passwords)?;
+                                start_config(name, &provider, &options, ask_all, job)
+                            });
+                        }
+                    }
+                }
+                WizardPage::Question {
+                    state,
+                    option,
+                    error,
+                } => {
+                    ui.label(RichText::new(&option.name).strong());
+                    ui.label(&option.help);
+                    if !error.is_empty() {
+                        ui.label(RichText::new(&error).color(Color32::RED));
+                    }
+
+                    ui.add_space(4.0);
+
+                    let mut answer: Option<String> = None;
+                    if let Some(wizard) = &mut app.create_wizard {
+                        if option.name == "config_token" {
+                            let provider = wizard.provider.prefix.clone();
+                            answer = token_input(ui, &option, &mut wizard.answer, &provider);
+                        } else {
+                            let sub_provider = wizard.sub_provider().to_owned();
+                            option_input(ui, &option, &mut wizard.answer, &sub_provider);
+                            answer = match option.validate(&wizard.answer) {
+                                Ok(()) => Some(wizard.answer.trim().to_owned()),
+                                Err(err) => {
+                                    ui.label(RichText::new(err).color(Color32::RED));
+                                    None
+                                }
+                            };
+                        }
+                    }
+
+                    ui.add_space(8.0);
+                    if ui
+                        .add_enabled(answer.is_some(), Button::new("Continue"))
+                        .clicked()
+                    {
+                        if let (Some(wizard), Some(answer)) = (&mut app.create_wizard, answer) {
+                            wizard.error = None;
+                            wizard.page = WizardPage::Running;
+
+                            send_step(app, ui.ctx(), move |name, job| {
+                                continue_config(name, &state, &answer, job)
+                            });
+                        }
+                    }
+                }
+                WizardPage::Running => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Waiting for rclone, finish any browser sign in it opened");
+                    });
+
+                    let elapsed = app
+                        .create_wizard
+                        .as_ref()
+                        .and_then(|wizard| wizard.job.as_ref())
+                        .map(|job| job.elapsed().as_secs())
+                        .unwrap_or_default();
+                    ui.label(
+                        RichText::new(format!("{} s of {} s", elapsed, CONFIG_TIMEOUT.as_secs()))
+                            .small()
+                            .weak(),
+                    );
+                    ui.ctx().request_repaint_after(Duration::from_secs(1));
+                }
+                WizardPage::Acknowledge => {
+                    if render_acknowledge(ui, app) {
+                        app.finish_create_wizard();
+                        return;
+                    }
+                }
+            }
+
+            if let Some(error) = app.create_wizard.as_ref().and_then(|w| w.error.as_ref()) {
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+
+            ui.add_space(4.0);
+            if !is_acknowledging && ui.button("Cancel").clicked() {
+                cancel = true;
+            }
+        });
+
+    if cancel {
+        if let Some(wizard) = app.create_wizard.take() {
+            // a half configured remote would only show up broken in the list
+            if let Some(job) = &wizard.job {
+                job.cancel();
+            } else if wizard.is_created {
+                tokio::task::spawn_blocking(move || {
+                    if let Err(err) = Rclone::delete_remote(&wizard.name) {
+                        tracing::error!("Failed to remove {}: {}", wizard.name, err);
+                    }
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+
+use egui::{Button, Color32, ComboBox, Grid, RichText, TextEdit, Ui};
+
+use crate::{
+    backend::rclone::{Rclone, SharedDrive},
+    ui::option_editor::send_update,
+    utilities::enums::{DriveScope, Message},
+    DriveFUSE,
+};
+
+/// Options the Google Drive form handles itself instead of the generic option list.
+pub const DRIVE_FORM_OPTIONS: [&str; 3] = ["scope", "root_folder_id", "shared_with_me"];
+
+/// Shared drives and drafts of the selected Google Drive storage.
+#[derive(Debug, Default)]
+pub struct DriveOptions {
+    storage: String,
+    shared_drives: Option<Vec<SharedDrive>>,
+    is_listing: bool,
+    root_folder_id: Option<String>,
+    scope_changed: bool,
+    pub error: Option<String>,
+}
+
+impl DriveOptions {
+    pub fn finish_listing(&mut self, storage: &str, result: Result<Vec<SharedDrive>, String>) {
+        if self.storage != storage {
+            return;
+        }
+
+        self.is_listing = false;
+        match result {
+            Ok(drives) => {
+                self.shared_drives = Some(drives);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Failed to list shared drives: {}", err)),
+        }
+    }
+
+    fn select(&mut self, storage: &str) {
+        if self.storage != storage {
+            *self = Self {
+                storage: storage.to_owned(),
+                ..Default::default()
+            };
+        }
+    }
+}
+
+fn scope_combo(ui: &mut Ui, id: &str, value: &str) -> Option<DriveScope> {
+    let mut selected = DriveScope::from_value(value);
+    let before = selected;
+
+    let text = match selected {
+        Some(scope) => scope.name().to_owned(),
+        None if value.is_empty() => DriveScope::Full.name().to_owned(),
+        None => value.to_owned(),
+    };
+    ComboBox::from_id_source(id)
+        .selected_text(text)
+        .show_ui(ui, |ui| {
+            for scope in DriveScope::values() {
+                ui.selectable_value(&mut selected, Some(scope), scope.name())
+                    .on_hover_text(scope.value());
+            }
+        });
+
+    if selected != before {
+        selected
+    } else {
+        None
+    }
+}
+
+/// Drive specific fields of the creation wizard, written into its option values.
+pub fn render_drive_create(ui: &mut Ui, values: &mut BTreeMap<String, String>) {
+    Grid::new("drive_create_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            ui.label("Access");
+            let scope = values.get("scope").cloned().unwrap_or_default();
+            if let Some(scope) = scope_combo(ui, "drive_create_scope", &scope) {
+                values.insert("scope".to_owned(), scope.value().to_owned());
+            }
+            ui.end_row();
+
+            ui.label("Root folder ID").on_hover_text(
+                "Mount a folder instead of the whole drive, the ID is the last part of its URL",
+            );
+            ui.add(
+                TextEdit::singleline(values.entry("root_folder_id".to_owned()).or_default())
+                    .hint_text("whole drive")
+                    .desired_width(220.0),
+            );
+            ui.end_row();
+
+            ui.label("Shared with me");
+            let mut shared_with_me = values.get("shared_with_me").is_some_and(|v| v == "true");
+            if ui
+                .checkbox(
+                    &mut shared_with_me,
+                    "Show files shared with me instead of my drive",
+                )
+                .changed()
+            {
+                values.insert("shared_with_me".to_owned(), shared_with_me.to_string());
+            }
+            ui.end_row();
+        });
+
+    ui.label(
+        RichText::new("rclone offers the shared drives you can access after signing in")
+            .small()
+            .weak(),
+    );
+}
+
+/// Drive settings of an existing storage, saved through `rclone config update`.
+pub fn render_drive_options(ui: &mut Ui, app: &mut DriveFUSE, name: &str) {
+    app.drive_options.select(name);
+
+    let options = match app.rclone.storages.iter().find(|s| s.name == name) {
+        Some(storage) => storage.options.clone(),
+        None => return,
+    };
+    let ctx = ui.ctx().clone();
+    let mut updates: Vec<(String, Option<String>)> = vec![];
+    let mut reconnect = false;
+
+    Grid::new("drive_options_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            // * Scope
+            ui.label("Access");
+            ui.horizontal(|ui| {
+                let scope = options.get("scope").cloned().unwrap_or_default();
+                if let Some(scope) = scope_combo(ui, "drive_options_scope", &scope) {
+                    updates.push(("scope".to_owned(), Some(scope.value().to_owned())));
+                    app.drive_options.scope_changed = true;
+                }
+                if app.drive_options.scope_changed
+                    && ui
+                        .button("Reconnect")
+                        .on_hover_text("The token only grants the scope it was created with")
+                        .clicked()
+                {
+                    app.drive_options.scope_changed = false;
+                    reconnect = true;
+                }
+            });
+            ui.end_row();
+
+            // * Shared drive
+            ui.label("Shared drive");
+            ui.horizontal(|ui| {
+                let team_drive = options.get("team_drive").cloned().unwrap_or_default();
+                let selected_text = match &app.drive_options.shared_drives {
+                    _ if team_drive.is_empty() => "My Drive".to_owned(),
+                    Some(drives) => drives
+                        .iter()
+                        .find(|drive| drive.id == team_drive)
+                        .map(|drive| drive.name.clone())
+                        .unwrap_or_else(|| team_drive.clone()),
+                    None => team_drive.clone(),
+                };
+
+                match &app.drive_options.shared_drives {
+                    Some(drives) => {
+                        ComboBox::from_id_source("drive_options_team_drive")
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                if ui
+                                    .selectable_label(team_drive.is_empty(), "My Drive")
+                                    .clicked()
+                                    && !team_drive.is_empty()
+                                {
+                                    updates.push(("team_drive".to_owned(), None));
+                                }
+                                for drive in drives {
+                                    if ui
+                                        .selectable_label(drive.id == team_drive, &drive.name)
+                                        .on_hover_text(&drive.id)
+                                        .clicked()
+                                        && drive.id != team_drive
+                                    {
+                                        updates.push((
+                                            "team_drive".to_owned(),
+                                            Some(drive.id.clone()),
+                                        ));
+                                    }
+                                }
+                            });
+                    }
+                    None => {
+                        ui.label(selected_text);
+                    }
+                }
+
+                let is_listing = app.drive_options.is_listing;
+                if ui
+                    .add_enabled(!is_listing, Button::new("List shared drives"))
+                    .clicked()
+                {
+                    app.drive_options.is_listing = true;
+
+                    let tx = app.tx_egui.clone();
+                    let ctx = ctx.clone();
+                    let storage = name.to_owned();
+                    tokio::task::spawn_blocking(move || {
+                        let result = Rclone::list_shared_drives(&storage);
+                        tx.send(Message::SharedDrivesListed { storage, result })
+                            .expect("Failed to send SharedDrivesListed message");
+                        ctx.request_repaint();
+                    });
+                }
+                if is_listing {
+                    ui.spinner();
+                }
+            });
+            ui.end_row();
+
+            // * Root folder
+            ui.label("Root folder ID");
+            ui.horizontal(|ui| {
+                let current = options.get("root_folder_id").cloned().unwrap_or_default();
+                let draft = app
+                    .drive_options
+                    .root_folder_id
+                    .get_or_insert_with(|| current.clone());
+                ui.add(
+                    TextEdit::singleline(draft)
+                        .hint_text("whole drive")
+                        .desired_width(220.0),
+                );
+
+                let draft = draft.trim().to_owned();
+                if ui
+                    .add_enabled(draft != current, Button::new("Save"))
+                    .clicked()
+                {
+                    let value = (!draft.is_empty()).then_some(draft);
+                    updates.push(("root_folder_id".to_owned(), value));
+                    app.drive_options.root_folder_id = None;
+                }
+            });
+            ui.end_row();
+
+            // * Shared with me
+            ui.label("Shared with me");
+            let mut shared_with_me = options.get("shared_with_me").is_some_and(|v| v == "true");
+            if ui
+                .checkbox(
+                    &mut shared_with_me,
+                    "Show files shared with me instead of my drive",
+                )
+                .changed()
+            {
+                let value = shared_with_me.then(|| "true".to_owned());
+                updates.push(("shared_with_me".to_owned(), value));
+            }
+            ui.end_row();
+        });
+
+    if let Some(error) = &app.drive_options.error {
+        ui.label(RichText::new(error).color(Color32::RED));
+    }
+
+    for (key, value) in updates {
+        send_update(app, &ctx, name.to_owned(), key, value);
+    }
+    if reconnect {
+        app.reconnect_storage(&ctx, name.to_owned());
+    }
+}
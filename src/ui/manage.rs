@@ -1,6 +1,15 @@
-use egui::{vec2, Button, CentralPanel, Context, CursorIcon, Grid, RichText, Rounding, ScrollArea};
+use egui::{
+    Align2, Button, CentralPanel, CollapsingHeader, Color32, ComboBox, Context, CursorIcon, Grid,
+    RichText, Rounding, ScrollArea, TextEdit, Ui, Window,
+};
 
-use crate::{utilities::enums::StorageType, DriveFUSE};
+use crate::{
+    ui::{
+        bundle::render_bundle, create_wizard::CreateWizard, drive_options::render_drive_options,
+        option_editor::render_option_editor,
+    },
+    DriveFUSE,
+};
 
 pub fn render_manage(ctx: &Context, app: &mut DriveFUSE) {
     CentralPanel::default().show(ctx, |ui| {
@@ -18,112 +27,337 @@ pub fn render_manage(ctx: &Context, app: &mut DriveFUSE) {
                     ui.text_edit_singleline(&mut app.new_storage_name);
                 });
 
+                let name_check = match app.new_storage_name.is_empty() {
+                    true => None,
+                    false => Some(app.validate_storage_name(&app.new_storage_name)),
+                };
+                if let Some(Err(error)) = &name_check {
+                    ui.label(RichText::new(error).color(Color32::RED));
+                }
+
                 ui.add_space(8.0);
 
-                Grid::new("add_storage_grid")
-                    .striped(false)
-                    .num_columns(4)
-                    .spacing([8.0, 8.0])
-                    .show(ui, |ui| {
-                        for (idx, storage_type) in StorageType::values().iter().enumerate() {
-                            if idx % 4 == 0 && idx != 0 {
-                                ui.end_row();
-                            }
+                ui.horizontal(|ui| {
+                    ui.label("Provider: ");
 
-                            let btn = ui
-                                .add_sized(
-                                    vec2(100.0, 30.0),
-                                    Button::new(storage_type.name()).rounding(Rounding::same(5.)),
+                    let selected_text = match &app.new_storage_type {
+                        Some(prefix) => app.providers.display_name(prefix).to_owned(),
+                        None => "Select provider".to_string(),
+                    };
+                    ComboBox::from_id_source("new_storage_type")
+                        .selected_text(selected_text)
+                        .width(220.0)
+                        .show_ui(ui, |ui| {
+                            for provider in &app.providers.providers {
+                                ui.selectable_value(
+                                    &mut app.new_storage_type,
+                                    Some(provider.prefix.clone()),
+                                    provider.display_name(),
                                 )
-                                .on_hover_cursor(CursorIcon::PointingHand);
-                            if btn.clicked() {
-                                app.rclone
-                                    .add_storage(app.new_storage_name.clone(), *storage_type);
-                                app.new_storage_name = String::new();
+                                .on_hover_text(&provider.description);
+                            }
+                        });
+
+                    let can_add =
+                        matches!(name_check, Some(Ok(()))) && app.new_storage_type.is_some();
+                    let btn = ui
+                        .add_enabled(
+                            can_add,
+                            Button::new("Add storage").rounding(Rounding::same(5.)),
+                        )
+                        .on_hover_cursor(CursorIcon::PointingHand);
+                    if btn.clicked() {
+                        let provider = app
+                            .new_storage_type
+                            .as_ref()
+                            .and_then(|prefix| app.providers.get(prefix))
+                            .cloned();
+                        if let Some(provider) = provider {
+                            let name = app.new_storage_name.clone();
+                            app.create_wizard = Some(CreateWizard::new(name, provider));
+                            app.new_storage_name.clear();
+                        }
+                    }
+                });
+
+                ui.label(
+                    RichText::new(format!(
+                        "{} providers from the {}",
+                        app.providers.providers.len(),
+                        app.providers.source.name()
+                    ))
+                    .small()
+                    .weak(),
+                );
+
+                ui.add_space(8.0);
+
+                // * Select storage
+                ComboBox::from_label("")
+                    .selected_text({
+                        match app.selected_storage.clone() {
+                            Some(storage) => storage,
+                            None => "Select storage".to_string(),
+                        }
+                    })
+                    .show_ui(ui, |ui| {
+                        for storage in &app.rclone.storages {
+                            let resp = ui.selectable_value(
+                                &mut app.selected_storage,
+                                Some(storage.name.clone()),
+                                storage.name.clone(),
+                            );
+                            if resp.clicked() {
+                                app.edit_storage_name = storage.name.clone();
+                                app.edit_storage_error = None;
+                                app.edit_mount_root = app
+                                    .app_config
+                                    .mount_roots
+                                    .get(&storage.name)
+                                    .cloned()
+                                    .unwrap_or_default();
+                                ui.close_menu();
                             }
                         }
                     });
 
-                // ui.add_space(8.0);
-
-                // // * Select storage
-                // ComboBox::from_label("")
-                //     .selected_text({
-                //         match app.selected_storage.clone() {
-                //             Some(storage) => storage,
-                //             None => "Select storage".to_string(),
-                //         }
-                //     })
-                //     .show_ui(ui, |ui| {
-                //         for storage in &app.rclone.storages {
-                //             let resp = ui.selectable_value(
-                //                 &mut app.selected_storage,
-                //                 Some(storage.name.clone()),
-                //                 storage.name.clone(),
-                //             );
-                //             if resp.clicked() {
-                //                 app.edit_storage_name = storage.name.clone();
-                //                 ui.close_menu();
-                //             }
-                //         }
-                //     });
-
-                // ui.add_space(8.0);
-
-                // // * Edit storage
-                // match app.selected_storage.clone() {
-                //     Some(name) => {
-                //         let storage = app
-                //             .rclone
-                //             .storages
-                //             .iter()
-                //             .find(|s| s.name == name)
-                //             .expect()
-                //             .clone();
-
-                //         ui.horizontal(|ui| {
-                //             ui.label("Name:");
-                //             ui.text_edit_singleline(&mut app.edit_storage_name);
-                //         });
-
-                //         ui.add_space(8.0);
-
-                //         if ui.button("Edit name (double ckick)").double_clicked() {
-                //             let old_name = storage.name.clone();
-                //             let new_name = app.edit_storage_name.clone();
-
-                //             let index = app
-                //                 .rclone
-                //                 .storages
-                //                 .iter()
-                //                 .position(|temp| temp.name == old_name)
-                //                 .expect();
-                //             app.rclone.storages[index] = Storage {
-                //                 name: new_name.clone(),
-                //                 drive_type: app.rclone.storages[index].drive_type.clone(),
-                //                 scope: app.rclone.storages[index].scope.clone(),
-                //                 token: app.rclone.storages[index].token.clone(),
-                //             };
-
-                //             app.rclone.edit_storage_name(old_name, new_name.clone());
-                //             app.edit_storage_name = String::new();
-                //             app.selected_storage = Some(new_name);
-                //         }
-
-                //         ui.add_space(8.0);
-
-                //         if ui.button("Delete storage (double ckick)").double_clicked() {
-                //             let is_mounted = app.mounted_storages.is_mounted(storage.name.clone());
-                //             if is_mounted {
-                //                 app.mounted_storages.unmount(storage.name.clone());
-                //             }
-                //             app.rclone.remove_storage(storage.name.clone());
-                //         }
-                //     }
-                //     None => {
-                //         ui.label("Please select a storage to edit its name");
-                //     }
-                // }
+                ui.add_space(8.0);
+
+                // * Edit storage
+                match app.selected_storage.clone() {
+                    Some(name) => {
+                        ui.horizontal(|ui| {
+                            ui.label("Name:");
+                            ui.text_edit_singleline(&mut app.edit_storage_name);
+
+                            let is_changed = app.edit_storage_name != name;
+                            let name_check = is_changed
+                                .then(|| app.validate_storage_name(&app.edit_storage_name));
+                            if let Some(Err(error)) = &name_check {
+                                ui.label(RichText::new(error).color(Color32::RED));
+                            }
+                            if ui
+                                .add_enabled(
+                                    matches!(name_check, Some(Ok(()))),
+                                    Button::new("Rename"),
+                                )
+                                .on_hover_text("Mounted storages are unmounted first")
+                                .clicked()
+                            {
+                                let new_name = app.edit_storage_name.clone();
+                                match app.rename_storage(&name, &new_name) {
+                                    Ok(()) => {
+                                        app.selected_storage = Some(new_name);
+                                        app.edit_storage_error = None;
+                                    }
+                                    Err(err) => app.edit_storage_error = Some(err),
+                                }
+                            }
+                        });
+
+                        if let Some(error) = &app.edit_storage_error {
+                            ui.label(RichText::new(error).color(Color32::RED));
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.label("Mount root:");
+                            ui.add(
+                                TextEdit::singleline(&mut app.edit_mount_root)
+                                    .hint_text("whole remote"),
+                            );
+
+                            let current = app
+                                .app_config
+                                .mount_roots
+                                .get(&name)
+                                .cloned()
+                                .unwrap_or_default();
+                            let is_changed =
+                                app.edit_mount_root.trim().trim_matches('/') != current;
+                            if ui
+                                .add_enabled(is_changed, Button::new("Save"))
+                                .on_hover_text("Folder or bucket to mount, applies on next mount")
+                                .clicked()
+                            {
+                                app.app_config
+                                    .set_mount_root(name.clone(), app.edit_mount_root.clone());
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label("Duplicate as:");
+                            ui.add(
+                                TextEdit::singleline(&mut app.duplicate_storage_name)
+                                    .hint_text("new name"),
+                            );
+
+                            let name_check = match app.duplicate_storage_name.is_empty() {
+                                true => None,
+                                false => {
+                                    Some(app.validate_storage_name(&app.duplicate_storage_name))
+                                }
+                            };
+                            if ui
+                                .add_enabled(
+                                    matches!(name_check, Some(Ok(()))),
+                                    Button::new("Duplicate"),
+                                )
+                                .on_hover_text(
+                                    "Copies every option including the token, change the \
+                                     shared drive, folder or bucket afterwards",
+                                )
+                                .clicked()
+                            {
+                                let new_name = app.duplicate_storage_name.clone();
+                                app.edit_storage_error =
+                                    app.duplicate_storage(&name, &new_name).err();
+                            }
+                            if let Some(Err(error)) = &name_check {
+                                ui.label(RichText::new(error).color(Color32::RED));
+                            }
+                        });
+
+                        ui.add_space(8.0);
+
+                        let is_drive = app
+                            .rclone
+                            .storages
+                            .iter()
+                            .any(|s| s.name == name && s.drive_type == "drive");
+                        if is_drive {
+                            CollapsingHeader::new("Google Drive")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    render_drive_options(ui, app, &name);
+                                });
+                        }
+
+                        CollapsingHeader::new("Options")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                render_option_editor(ui, app, &name);
+                            });
+
+                        ui.add_space(8.0);
+
+                        if ui
+                            .button(RichText::new("Delete storage").color(Color32::RED))
+                            .clicked()
+                        {
+                            app.delete_storage = Some(name.clone());
+                        }
+                    }
+                    None => {
+                        ui.label("Please select a storage to edit it");
+                    }
+                }
+
+                ui.add_space(8.0);
+
+                CollapsingHeader::new("Export / Import").show(ui, |ui| {
+                    render_bundle(ui, app);
+                });
+
+                if !app.trash.entries.is_empty() {
+                    CollapsingHeader::new(format!("Trash ({})", app.trash.entries.len())).show(
+                        ui,
+                        |ui| {
+                            render_trash(ui, app);
+                        },
+                    );
+                }
             });
     });
+
+    render_delete_confirmation(ctx, app);
+}
+
+fn render_trash(ui: &mut Ui, app: &mut DriveFUSE) {
+    let mut restore: Option<usize> = None;
+
+    Grid::new("trash_grid")
+        .num_columns(4)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            for (index, entry) in app.trash.entries.iter().enumerate().rev() {
+                let drive_type = entry
+                    .remote
+                    .options
+                    .get("type")
+                    .map_or("", |prefix| app.providers.display_name(prefix));
+
+                ui.label(&entry.remote.name);
+                ui.label(drive_type);
+                ui.label(RichText::new(&entry.deleted_at).weak());
+                if ui.button("Restore").clicked() {
+                    restore = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some(error) = &app.trash_error {
+        ui.label(RichText::new(error).color(Color32::RED));
+    }
+    ui.label(
+        RichText::new("Kept with their tokens in DriveFUSE's config folder")
+            .small()
+            .weak(),
+    );
+
+    if let Some(index) = restore {
+        app.trash_error = app.restore_storage(index).err();
+    }
+}
+
+fn render_delete_confirmation(ctx: &Context, app: &mut DriveFUSE) {
+    let name = match &app.delete_storage {
+        Some(name) => name.clone(),
+        None => return,
+    };
+    let mut close = false;
+
+    Window::new("Delete storage")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("Delete {}?", name));
+            if app.rclone.is_encrypted {
+                ui.label(
+                    "It is unmounted and its cache is cleared. The files on the provider are \
+                     not touched.",
+                );
+                ui.label(
+                    RichText::new(
+                        "The rclone config is encrypted, so the storage is not kept in the \
+                         trash and this cannot be undone.",
+                    )
+                    .color(Color32::YELLOW),
+                );
+            } else {
+                ui.label(
+                    "It is unmounted and its cache is cleared. The files on the provider are \
+                     not touched, and the storage can be restored from the trash.",
+                );
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .button(RichText::new("Delete").color(Color32::RED))
+                    .clicked()
+                {
+                    app.edit_storage_error = app.delete_storage(&name).err();
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if close {
+        app.delete_storage = None;
+    }
 }
@@ -1,4 +1,7 @@
-use egui::{vec2, Button, CentralPanel, Color32, Context, Grid, RichText, Rounding, ScrollArea};
+use egui::{
+    vec2, Button, CentralPanel, CollapsingHeader, Color32, Context, Grid, RichText, Rounding,
+    ScrollArea,
+};
 
 #[cfg(target_os = "windows")]
 use {crate::utilities::utils::available_drives, egui::ComboBox};
@@ -6,7 +9,10 @@ use {crate::utilities::utils::available_drives, egui::ComboBox};
 #[cfg(target_family = "unix")]
 use crate::utilities::utils::open_drive_location;
 
-use crate::{utilities::enums::AppTheme, DriveFUSE};
+use crate::{
+    utilities::enums::{AppTheme, TestFailure, TokenState},
+    DriveFUSE,
+};
 
 pub fn render_mount_unmount(ctx: &Context, app: &mut DriveFUSE) {
     CentralPanel::default().show(ctx, |ui| {
@@ -22,6 +28,54 @@ pub fn render_mount_unmount(ctx: &Context, app: &mut DriveFUSE) {
             .auto_shrink([false, true])
             .drag_to_scroll(false)
             .show(ui, |ui| {
+                if !app.rclone.errors.is_empty() {
+                    CollapsingHeader::new(
+                        RichText::new(format!(
+                            "{} problem(s) found in rclone config",
+                            app.rclone.errors.len()
+                        ))
+                        .color(Color32::YELLOW),
+                    )
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        for error in &app.rclone.errors {
+                            ui.label(error.to_string());
+                        }
+                    });
+
+                    ui.add_space(8.0);
+                }
+
+                let needs_reconnect = app
+                    .rclone
+                    .storages
+                    .iter()
+                    .filter(|storage| {
+                        matches!(
+                            app.token_state(storage),
+                            Some(
+                                TokenState::RefreshFailed
+                                    | TokenState::Expired
+                                    | TokenState::Missing
+                            )
+                        )
+                    })
+                    .count();
+                if needs_reconnect > 0 {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} storage(s) must reconnect before mounting will work again",
+                            needs_reconnect
+                        ))
+                        .color(Color32::YELLOW),
+                    );
+
+                    ui.add_space(8.0);
+                }
+
+                let mut reconnect: Option<String> = None;
+                let mut test: Option<String> = None;
+
                 Grid::new("storage_grid")
                     .striped(app.app_config.current_theme == AppTheme::Dark)
                     .num_columns(4)
@@ -34,6 +88,8 @@ pub fn render_mount_unmount(ctx: &Context, app: &mut DriveFUSE) {
 
                         ui.label("Status");
 
+                        ui.label("Token");
+
                         #[cfg(target_os = "windows")]
                         ui.label("Drive Letter");
                         #[cfg(target_family = "unix")]
@@ -57,15 +113,7 @@ pub fn render_mount_unmount(ctx: &Context, app: &mut DriveFUSE) {
 
                             let action_text = if is_mounted { "Unmount" } else { "Mount" };
 
-                            let drive_type = match storage.drive_type.as_str() {
-                                "drive" => "Google Drive",
-                                "onedrive" => "OneDrive",
-                                "dropbox" => "Dropbox",
-                                "googlephotos" => "Google Photos",
-                                "mega" => "Mega",
-                                "nextcloud" => "NextCloud",
-                                _ => "Unknown",
-                            };
+                            let drive_type = app.providers.display_name(&storage.drive_type);
 
                             ui.label(if app.app_config.hide_storage_label {
                                 "*".repeat(storage.name.len())
@@ -73,7 +121,93 @@ pub fn render_mount_unmount(ctx: &Context, app: &mut DriveFUSE) {
                                 storage.name.clone()
                             });
                             ui.label(drive_type);
-                            ui.label(status_text);
+                            ui.horizontal(|ui| {
+                                ui.label(status_text);
+
+                                let is_testing = app.testing.contains(&storage.name);
+                                if ui
+                                    .add_enabled(!is_testing, Button::new("Test").small())
+                                    .on_hover_text("Lists the top folder with rclone lsd")
+                                    .clicked()
+                                {
+                                    test = Some(storage.name.clone());
+                                }
+
+                                if is_testing {
+                                    ui.spinner();
+                                } else {
+                                    match app.storage_tests.get(&storage.name) {
+                                        Some(Ok(latency)) => {
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "{} ms",
+                                                    latency.as_millis()
+                                                ))
+                                                .color(Color32::GREEN),
+                                            );
+                                        }
+                                        Some(Err(err)) => {
+                                            let failure = TestFailure::from_error(err);
+                                            ui.label(
+                                                RichText::new(failure.name()).color(Color32::RED),
+                                            )
+                                            .on_hover_text(err);
+                                        }
+                                        None => {}
+                                    }
+                                }
+                            });
+
+                            match app.token_state(storage) {
+                                Some(state) => {
+                                    ui.horizontal(|ui| {
+                                        let hover = match state {
+                                            TokenState::RefreshFailed => app
+                                                .token_failure(&storage.name)
+                                                .cloned()
+                                                .unwrap_or_default(),
+                                            TokenState::Expired | TokenState::Expiring => {
+                                                "There is no refresh token, reconnect to keep \
+                                                 mounting this storage"
+                                                    .to_owned()
+                                            }
+                                            TokenState::Missing => {
+                                                "There is no token, reconnect to sign in".to_owned()
+                                            }
+                                            TokenState::Valid => {
+                                                "rclone refreshes it on use, Test checks that it \
+                                                 still works"
+                                                    .to_owned()
+                                            }
+                                        };
+                                        ui.label(RichText::new(state.name()).color(state.color()))
+                                            .on_hover_text(hover);
+
+                                        let is_reconnecting =
+                                            app.reconnecting.contains(&storage.name);
+                                        if state != TokenState::Valid || is_reconnecting {
+                                            let btn = ui
+                                                .add_enabled(
+                                                    !is_reconnecting,
+                                                    Button::new(if is_reconnecting {
+                                                        "Reconnecting..."
+                                                    } else {
+                                                        "Reconnect"
+                                                    })
+                                                    .small(),
+                                                )
+                                                .on_hover_text("Runs rclone config reconnect");
+                                            if btn.clicked() {
+                                                reconnect = Some(storage.name.clone());
+                                            }
+                                        }
+                                    });
+                                }
+                                None => {
+                                    ui.label("-");
+                                }
+                            }
+
                             #[cfg(target_os = "windows")]
                             let letter = app
                                 .app_config
@@ -239,6 +373,13 @@ pub fn render_mount_unmount(ctx: &Context, app: &mut DriveFUSE) {
                             ui.end_row();
                         }
                     });
+
+                if let Some(name) = reconnect {
+                    app.reconnect_storage(ctx, name);
+                }
+                if let Some(name) = test {
+                    app.test_storage(ctx, name);
+                }
             });
     });
 }
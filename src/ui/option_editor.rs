@@ -0,0 +1,204 @@
+use std::collections::{BTreeMap, HashSet};
+
+use egui::{Button, Color32, Grid, RichText, TextEdit, Ui};
+
+use crate::{
+    backend::rclone::{is_secret_option, Rclone},
+    utilities::enums::Message,
+    DriveFUSE,
+};
+
+/// Unsaved edits of the selected storage's options.
+#[derive(Debug, Default)]
+pub struct OptionEditor {
+    storage: String,
+    drafts: BTreeMap<String, String>,
+    revealed: HashSet<String>,
+    pending: HashSet<String>,
+    new_key: String,
+    new_value: String,
+    pub error: Option<String>,
+}
+
+impl OptionEditor {
+    /// Called when rclone reported back on an update sent from this editor.
+    pub fn finish_update(&mut self, storage: &str, key: &str, result: Result<(), String>) {
+        self.pending.remove(key);
+        if self.storage != storage {
+            return;
+        }
+
+        match result {
+            Ok(()) => {
+                self.drafts.remove(key);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Failed to update {}: {}", key, err)),
+        }
+    }
+
+    fn select(&mut self, storage: &str) {
+        if self.storage != storage {
+            *self = Self {
+                storage: storage.to_owned(),
+                ..Default::default()
+            };
+        }
+    }
+}
+
+/// `None` removes the option.
+pub fn send_update(
+    app: &DriveFUSE,
+    ctx: &egui::Context,
+    storage: String,
+    key: String,
+    value: Option<String>,
+) {
+    let tx = app.tx_egui.clone();
+    let ctx = ctx.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = match value {
+            Some(value) => Rclone::update_option(&storage, &key, &value),
+            None => Rclone::remove_option(&storage, &key),
+        };
+        tx.send(Message::StorageOptionUpdated {
+            storage,
+            key,
+            result,
+        })
+        .expect("Failed to send StorageOptionUpdated message");
+        ctx.request_repaint();
+    });
+}
+
+pub fn render_option_editor(ui: &mut Ui, app: &mut DriveFUSE, name: &str) {
+    app.option_editor.select(name);
+
+    let options = match app.rclone.storages.iter().find(|s| s.name == name) {
+        Some(storage) => storage.options.clone(),
+        None => return,
+    };
+
+    let ctx = ui.ctx().clone();
+    let mut updates: Vec<(String, Option<String>)> = vec![];
+
+    Grid::new("option_editor_grid")
+        .num_columns(3)
+        .spacing([8.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (key, value) in &options {
+                ui.label(key);
+
+                let editor = &mut app.option_editor;
+                let is_pending = editor.pending.contains(key);
+
+                // changing the backend type would make every other option meaningless
+                if key == "type" {
+                    ui.label(value);
+                    ui.label("");
+                    ui.end_row();
+                    continue;
+                }
+
+                let is_secret = is_secret_option(key) && !editor.revealed.contains(key);
+                let mut draft = editor
+                    .drafts
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| value.clone());
+                let response = ui.add_enabled(
+                    !is_pending,
+                    TextEdit::singleline(&mut draft)
+                        .password(is_secret)
+                        .desired_width(220.0),
+                );
+                if response.changed() {
+                    if &draft == value {
+                        editor.drafts.remove(key);
+                    } else {
+                        editor.drafts.insert(key.clone(), draft.clone());
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    if is_secret_option(key) {
+                        let is_revealed = editor.revealed.contains(key);
+                        if ui
+                            .small_button(if is_revealed { "Hide" } else { "Show" })
+                            .clicked()
+                        {
+                            if is_revealed {
+                                editor.revealed.remove(key);
+                            } else {
+                                editor.revealed.insert(key.clone());
+                            }
+                        }
+                    }
+
+                    let is_changed = &draft != value;
+                    if ui
+                        .add_enabled(is_changed && !is_pending, Button::new("Save"))
+                        .clicked()
+                    {
+                        updates.push((key.clone(), Some(draft.clone())));
+                    }
+                    if ui
+                        .add_enabled(is_changed && !is_pending, Button::new("Revert"))
+                        .clicked()
+                    {
+                        editor.drafts.remove(key);
+                    }
+                    if ui
+                        .add_enabled(!is_pending, Button::new("Remove"))
+                        .on_hover_text("Removes the option so rclone uses its default")
+                        .clicked()
+                    {
+                        updates.push((key.clone(), None));
+                    }
+                });
+
+                ui.end_row();
+            }
+
+            // drafts of keys that vanished from the config are stale
+            app.option_editor
+                .drafts
+                .retain(|key, _| options.contains_key(key));
+
+            ui.add(
+                TextEdit::singleline(&mut app.option_editor.new_key)
+                    .hint_text("key")
+                    .desired_width(100.0),
+            );
+            ui.add(
+                TextEdit::singleline(&mut app.option_editor.new_value)
+                    .hint_text("value")
+                    .desired_width(220.0),
+            );
+            let new_key = app.option_editor.new_key.trim().to_owned();
+            let can_add = !new_key.is_empty()
+                && !options.contains_key(&new_key)
+                && !app.option_editor.pending.contains(&new_key);
+            if ui.add_enabled(can_add, Button::new("Add")).clicked() {
+                let value = std::mem::take(&mut app.option_editor.new_value);
+                app.option_editor.new_key.clear();
+                updates.push((new_key, Some(value)));
+            }
+            ui.end_row();
+        });
+
+    if !app.option_editor.drafts.is_empty() && ui.button("Revert all").clicked() {
+        app.option_editor.drafts.clear();
+    }
+
+    if let Some(error) = &app.option_editor.error {
+        ui.label(RichText::new(error).color(Color32::RED));
+    }
+
+    for (key, value) in updates {
+        app.option_editor.pending.insert(key.clone());
+        send_update(app, &ctx, name.to_owned(), key, value);
+    }
+}
@@ -0,0 +1,1150 @@
+use std::collections::BTreeMap;
+
+use directories::BaseDirs;
+use egui::{Button, Color32, ComboBox, Grid, RichText, ScrollArea, TextEdit, Ui};
+use rand::{distributions::Alphanumeric, Rng};
+
+use crate::{
+    backend::rclone::Rclone,
+    ui::drive_options::{render_drive_create, DRIVE_FORM_OPTIONS},
+    utilities::enums::{Message, S3Preset, WebdavVendor},
+    DriveFUSE,
+};
+
+const WEBDAV_FORM_OPTIONS: [&str; 5] = ["url", "vendor", "user", "pass", "bearer_token"];
+
+const S3_FORM_OPTIONS: [&str; 6] = [
+    "provider",
+    "endpoint",
+    "region",
+    "access_key_id",
+    "secret_access_key",
+    "acl",
+];
+
+const SFTP_FORM_OPTIONS: [&str; 7] = [
+    "host",
+    "port",
+    "user",
+    "pass",
+    "key_file",
+    "key_file_pass",
+    "known_hosts_file",
+];
+
+const FTP_FORM_OPTIONS: [&str; 6] = ["host", "port", "user", "pass", "tls", "explicit_tls"];
+
+const SMB_FORM_OPTIONS: [&str; 5] = ["host", "port", "user", "pass", "domain"];
+
+const CRYPT_FORM_OPTIONS: [&str; 5] = [
+    "remote",
+    "password",
+    "password2",
+    "filename_encryption",
+    "directory_name_encryption",
+];
+
+const UNION_FORM_OPTIONS: [&str; 4] = [
+    "upstreams",
+    "action_policy",
+    "create_policy",
+    "search_policy",
+];
+
+const COMBINE_FORM_OPTIONS: [&str; 1] = ["upstreams"];
+
+const ALIAS_FORM_OPTIONS: [&str; 1] = ["remote"];
+
+/// Union policies with what they pick, rclone's defaults are epall, epmfs and ff.
+const UNION_POLICIES: [(&str, &str); 13] = [
+    ("epall", "Existing path, all"),
+    ("epff", "Existing path, first found"),
+    ("eplfs", "Existing path, least free space"),
+    ("eplus", "Existing path, least used space"),
+    ("epmfs", "Existing path, most free space"),
+    ("eprand", "Existing path, random"),
+    ("all", "All upstreams"),
+    ("ff", "First found"),
+    ("lfs", "Least free space"),
+    ("lus", "Least used space"),
+    ("mfs", "Most free space"),
+    ("newest", "Newest file"),
+    ("rand", "Random"),
+];
+
+/// Canned ACLs rclone applies to new buckets and objects, empty keeps rclone's default.
+const S3_ACLS: [&str; 6] = [
+    "private",
+    "public-read",
+    "public-read-write",
+    "authenticated-read",
+    "bucket-owner-read",
+    "bucket-owner-full-control",
+];
+
+/// Options a provider's own form fills in, left out of the wizard's generic list.
+pub fn form_options(provider: &str) -> &'static [&'static str] {
+    match provider {
+        "drive" => &DRIVE_FORM_OPTIONS,
+        "webdav" => &WEBDAV_FORM_OPTIONS,
+        "s3" => &S3_FORM_OPTIONS,
+        "sftp" => &SFTP_FORM_OPTIONS,
+        "ftp" => &FTP_FORM_OPTIONS,
+        "smb" => &SMB_FORM_OPTIONS,
+        "crypt" => &CRYPT_FORM_OPTIONS,
+        "union" => &UNION_FORM_OPTIONS,
+        "combine" => &COMBINE_FORM_OPTIONS,
+        "alias" => &ALIAS_FORM_OPTIONS,
+        _ => &[],
+    }
+}
+
+/// Password options of the provider's form, obscured before they are handed to rclone.
+pub fn form_passwords(provider: &str) -> &'static [&'static str] {
+    match provider {
+        "webdav" | "ftp" | "smb" => &["pass"],
+        "sftp" => &["pass", "key_file_pass"],
+        "crypt" => &["password", "password2"],
+        _ => &[],
+    }
+}
+
+/// Obscures the given keys and drops empty values.
+pub fn prepare_options(
+    values: &BTreeMap<String, String>,
+    passwords: &[String],
+) -> Result<BTreeMap<String, String>, String> {
+    let mut options = BTreeMap::new();
+    for (key, value) in values {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        let value = match passwords.contains(key) {
+            true => Rclone::obscure(value)?,
+            false => value.to_owned(),
+        };
+        options.insert(key.clone(), value);
+    }
+
+    Ok(options)
+}
+
+fn text_row(ui: &mut Ui, label: &str, value: &mut String, hint: &str, is_password: bool) {
+    ui.label(label);
+    ui.add(
+        TextEdit::singleline(value)
+            .password(is_password)
+            .hint_text(hint)
+            .desired_width(260.0),
+    );
+    ui.end_row();
+}
+
+fn render_webdav_form(ui: &mut Ui, values: &mut BTreeMap<String, String>) -> Vec<String> {
+    let mut errors = vec![];
+
+    Grid::new("webdav_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            text_row(
+                ui,
+                "URL *",
+                values.entry("url".to_owned()).or_default(),
+                "https://cloud.example.com/remote.php/dav/files/USER/",
+                false,
+            );
+
+            ui.label("Vendor");
+            let vendor = values.entry("vendor".to_owned()).or_default();
+            let selected = WebdavVendor::values()
+                .into_iter()
+                .find(|v| v.value() == vendor.as_str())
+                .map(|v| v.name().to_owned())
+                .unwrap_or_else(|| "Select vendor".to_owned());
+            ComboBox::from_id_source("webdav_form_vendor")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for option in WebdavVendor::values() {
+                        ui.selectable_value(vendor, option.value().to_owned(), option.name());
+                    }
+                });
+            ui.end_row();
+
+            text_row(
+                ui,
+                "User",
+                values.entry("user".to_owned()).or_default(),
+                "",
+                false,
+            );
+            text_row(
+                ui,
+                "Password",
+                values.entry("pass".to_owned()).or_default(),
+                "",
+                true,
+            );
+            text_row(
+                ui,
+                "Bearer token",
+                values.entry("bearer_token".to_owned()).or_default(),
+                "instead of user and password",
+                true,
+            );
+        });
+
+    let url = values.get("url").map(|url| url.trim()).unwrap_or_default();
+    if url.is_empty() {
+        errors.push("URL is required".to_owned());
+    } else if !url.starts_with("http://") && !url.starts_with("https://") {
+        errors.push("URL must start with http:// or https://".to_owned());
+    }
+    if values
+        .get("vendor")
+        .map_or(true, |vendor| vendor.is_empty())
+    {
+        errors.push("Pick the server's vendor".to_owned());
+    }
+
+    errors
+}
+
+fn render_s3_form(ui: &mut Ui, values: &mut BTreeMap<String, String>) -> Vec<String> {
+    let mut errors = vec![];
+    let preset = values
+        .get("provider")
+        .and_then(|provider| S3Preset::from_value(provider));
+
+    Grid::new("s3_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            ui.label("Provider *");
+            let mut selected = preset;
+            ComboBox::from_id_source("s3_form_provider")
+                .selected_text(
+                    selected
+                        .as_ref()
+                        .map_or("Select provider", |preset| preset.name()),
+                )
+                .show_ui(ui, |ui| {
+                    for preset in S3Preset::values() {
+                        ui.selectable_value(&mut selected, Some(preset), preset.name());
+                    }
+                });
+            if let Some(new_preset) = selected.filter(|new_preset| Some(*new_preset) != preset) {
+                values.insert("provider".to_owned(), new_preset.value().to_owned());
+                // the old region only fits the old provider
+                values.insert("region".to_owned(), new_preset.region().to_owned());
+            }
+            ui.end_row();
+
+            let endpoint_hint = preset
+                .as_ref()
+                .and_then(|preset| preset.endpoint())
+                .unwrap_or("derived from the region");
+            text_row(
+                ui,
+                "Endpoint",
+                values.entry("endpoint".to_owned()).or_default(),
+                endpoint_hint,
+                false,
+            );
+            text_row(
+                ui,
+                "Region",
+                values.entry("region".to_owned()).or_default(),
+                "",
+                false,
+            );
+            text_row(
+                ui,
+                "Access key ID",
+                values.entry("access_key_id".to_owned()).or_default(),
+                "",
+                false,
+            );
+            text_row(
+                ui,
+                "Secret access key",
+                values.entry("secret_access_key".to_owned()).or_default(),
+                "",
+                true,
+            );
+
+            ui.label("ACL");
+            let acl = values.entry("acl".to_owned()).or_default();
+            ComboBox::from_id_source("s3_form_acl")
+                .selected_text(if acl.is_empty() {
+                    "default"
+                } else {
+                    acl.as_str()
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(acl, String::new(), "default");
+                    for option in S3_ACLS {
+                        ui.selectable_value(acl, option.to_owned(), option);
+                    }
+                });
+            ui.end_row();
+        });
+
+    let is_set = |key: &str| {
+        values
+            .get(key)
+            .is_some_and(|value| !value.trim().is_empty())
+    };
+    match preset {
+        None => errors.push("Pick the S3 provider".to_owned()),
+        Some(S3Preset::Aws) => {}
+        Some(_) if !is_set("endpoint") => errors.push("Endpoint is required".to_owned()),
+        Some(_) => {}
+    }
+    if is_set("access_key_id") != is_set("secret_access_key") {
+        errors.push("Enter both keys, or neither for public buckets".to_owned());
+    }
+
+    errors
+}
+
+/// Host, port, user and password rows shared by the server backends.
+fn server_rows(
+    ui: &mut Ui,
+    values: &mut BTreeMap<String, String>,
+    default_port: u16,
+    errors: &mut Vec<String>,
+) {
+    text_row(
+        ui,
+        "Host *",
+        values.entry("host".to_owned()).or_default(),
+        "server.example.com",
+        false,
+    );
+    text_row(
+        ui,
+        "Port",
+        values.entry("port".to_owned()).or_default(),
+        &default_port.to_string(),
+        false,
+    );
+    text_row(
+        ui,
+        "User",
+        values.entry("user".to_owned()).or_default(),
+        "",
+        false,
+    );
+    text_row(
+        ui,
+        "Password",
+        values.entry("pass".to_owned()).or_default(),
+        "",
+        true,
+    );
+
+    if values
+        .get("host")
+        .map_or(true, |host| host.trim().is_empty())
+    {
+        errors.push("Host is required".to_owned());
+    }
+    let port = values
+        .get("port")
+        .map(|port| port.trim())
+        .unwrap_or_default();
+    if !port.is_empty() && port.parse::<u16>().is_err() {
+        errors.push("Port must be a number up to 65535".to_owned());
+    }
+}
+
+/// Path row with a button that asks for the file through `rfd`.
+fn file_row(
+    ui: &mut Ui,
+    label: &str,
+    key: &'static str,
+    values: &mut BTreeMap<String, String>,
+    browse: &mut Option<&'static str>,
+) {
+    ui.label(label);
+    ui.horizontal(|ui| {
+        ui.add(
+            TextEdit::singleline(values.entry(key.to_owned()).or_default()).desired_width(200.0),
+        );
+        if ui.button("Browse").clicked() {
+            *browse = Some(key);
+        }
+    });
+    ui.end_row();
+}
+
+fn render_sftp_form(
+    ui: &mut Ui,
+    values: &mut BTreeMap<String, String>,
+    browse: &mut Option<&'static str>,
+) -> Vec<String> {
+    let mut errors = vec![];
+
+    Grid::new("sftp_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            server_rows(ui, values, 22, &mut errors);
+            file_row(ui, "Key file", "key_file", values, browse);
+            text_row(
+                ui,
+                "Key passphrase",
+                values.entry("key_file_pass".to_owned()).or_default(),
+                "",
+                true,
+            );
+
+            // rclone skips host key checks unless it is given a known_hosts file
+            ui.label("Host key");
+            let mut verify = values
+                .get("known_hosts_file")
+                .is_some_and(|file| !file.trim().is_empty());
+            if ui
+                .checkbox(&mut verify, "Verify against known_hosts")
+                .changed()
+            {
+                let file = match verify {
+                    true => known_hosts_file().unwrap_or_default(),
+                    false => String::new(),
+                };
+                values.insert("known_hosts_file".to_owned(), file);
+            }
+            ui.end_row();
+
+            if verify {
+                file_row(ui, "known_hosts", "known_hosts_file", values, browse);
+            }
+        });
+
+    errors
+}
+
+fn known_hosts_file() -> Option<String> {
+    BaseDirs::new().map(|dirs| {
+        dirs.home_dir()
+            .join(".ssh")
+            .join("known_hosts")
+            .display()
+            .to_string()
+    })
+}
+
+fn render_ftp_form(ui: &mut Ui, values: &mut BTreeMap<String, String>) -> Vec<String> {
+    let mut errors = vec![];
+
+    Grid::new("ftp_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            server_rows(ui, values, 21, &mut errors);
+
+            ui.label("Encryption");
+            let selected = if values.get("tls").is_some_and(|v| v == "true") {
+                "Implicit TLS"
+            } else if values.get("explicit_tls").is_some_and(|v| v == "true") {
+                "Explicit TLS"
+            } else {
+                "None"
+            };
+            ComboBox::from_id_source("ftp_form_tls")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (label, tls, explicit_tls) in [
+                        ("None", "", ""),
+                        ("Implicit TLS", "true", ""),
+                        ("Explicit TLS", "", "true"),
+                    ] {
+                        if ui.selectable_label(selected == label, label).clicked() {
+                            values.insert("tls".to_owned(), tls.to_owned());
+                            values.insert("explicit_tls".to_owned(), explicit_tls.to_owned());
+                        }
+                    }
+                });
+            ui.end_row();
+        });
+
+    errors
+}
+
+fn render_smb_form(ui: &mut Ui, values: &mut BTreeMap<String, String>) -> Vec<String> {
+    let mut errors = vec![];
+
+    Grid::new("smb_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            server_rows(ui, values, 445, &mut errors);
+            text_row(
+                ui,
+                "Domain",
+                values.entry("domain".to_owned()).or_default(),
+                "WORKGROUP",
+                false,
+            );
+        });
+
+    errors
+}
+
+fn generate_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+fn secret_row(ui: &mut Ui, label: &str, value: &mut String, hint: &str) {
+    ui.label(label);
+    ui.horizontal(|ui| {
+        ui.add(
+            TextEdit::singleline(value)
+                .password(true)
+                .hint_text(hint)
+                .desired_width(200.0),
+        );
+        if ui.button("Generate").clicked() {
+            *value = generate_secret();
+        }
+    });
+    ui.end_row();
+}
+
+/// Encrypts a folder of an existing remote, `storages` being the remotes to pick from.
+fn render_crypt_form(
+    ui: &mut Ui,
+    values: &mut BTreeMap<String, String>,
+    storages: &[String],
+) -> Vec<String> {
+    let mut errors = vec![];
+
+    let remote = values.get("remote").cloned().unwrap_or_default();
+    let (mut base, mut path) = match remote.split_once(':') {
+        Some((base, path)) => (base.to_owned(), path.to_owned()),
+        None => (String::new(), String::new()),
+    };
+
+    Grid::new("crypt_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            ui.label("Remote *");
+            ComboBox::from_id_source("crypt_form_remote")
+                .selected_text(if base.is_empty() {
+                    "Select remote"
+                } else {
+                    base.as_str()
+                })
+                .show_ui(ui, |ui| {
+                    for storage in storages {
+                        ui.selectable_value(&mut base, storage.clone(), storage);
+                    }
+                });
+            ui.end_row();
+
+            ui.label("Folder");
+            ui.add(
+                TextEdit::singleline(&mut path)
+                    .hint_text("encrypted")
+                    .desired_width(200.0),
+            );
+            ui.end_row();
+
+            secret_row(
+                ui,
+                "Password *",
+                values.entry("password".to_owned()).or_default(),
+                "",
+            );
+            secret_row(
+                ui,
+                "Salt",
+                values.entry("password2".to_owned()).or_default(),
+                "optional, recommended",
+            );
+
+            ui.label("File names");
+            let filename_encryption = values.entry("filename_encryption".to_owned()).or_default();
+            ComboBox::from_id_source("crypt_form_filenames")
+                .selected_text(match filename_encryption.as_str() {
+                    "" | "standard" => "Encrypt",
+                    "obfuscate" => "Obfuscate",
+                    _ => "Leave readable",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(filename_encryption, "standard".to_owned(), "Encrypt");
+                    ui.selectable_value(filename_encryption, "obfuscate".to_owned(), "Obfuscate");
+                    ui.selectable_value(filename_encryption, "off".to_owned(), "Leave readable");
+                });
+            ui.end_row();
+
+            ui.label("Folder names");
+            let mut encrypt_dirs = values
+                .get("directory_name_encryption")
+                .map_or(true, |value| value != "false");
+            if ui.checkbox(&mut encrypt_dirs, "Encrypt").changed() {
+                values.insert(
+                    "directory_name_encryption".to_owned(),
+                    encrypt_dirs.to_string(),
+                );
+            }
+            ui.end_row();
+        });
+
+    if base.is_empty() {
+        errors.push("Pick the remote to encrypt".to_owned());
+        values.remove("remote");
+    } else {
+        values.insert(
+            "remote".to_owned(),
+            format!("{}:{}", base, path.trim().trim_matches('/')),
+        );
+    }
+    if values.get("password").map_or(true, |pass| pass.is_empty()) {
+        errors.push("Password is required".to_owned());
+    }
+
+    errors
+}
+
+/// An existing remote offered as an upstream of a union or combine remote.
+#[derive(Debug, Clone, Default)]
+pub struct Upstream {
+    pub remote: String,
+    pub selected: bool,
+    pub path: String,
+    /// Union only, "ro", "nc" or "writeback" appended to the upstream.
+    pub mode: String,
+    /// Combine only, the folder the upstream shows up as.
+    pub dir: String,
+}
+
+impl Upstream {
+    fn to_value(&self, combine: bool) -> String {
+        let mut value = format!("{}:{}", self.remote, self.path.trim().trim_matches('/'));
+        if combine {
+            value = format!("{}={}", self.dir.trim(), value);
+        } else if !self.mode.is_empty() {
+            value = format!("{}:{}", value, self.mode);
+        }
+
+        // upstreams are space separated, quoting keeps names with spaces together
+        match value.contains(' ') {
+            true => format!("\"{}\"", value),
+            false => value,
+        }
+    }
+}
+
+fn policy_row(ui: &mut Ui, label: &str, value: &mut String, default: &str) {
+    ui.label(label);
+    let selected_text = match value.as_str() {
+        "" => format!("{} (default)", default),
+        value => value.to_owned(),
+    };
+    ComboBox::from_id_source(format!("union_form_{}", label))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, String::new(), format!("{} (default)", default));
+            for (policy, help) in UNION_POLICIES {
+                ui.selectable_value(value, policy.to_owned(), policy)
+                    .on_hover_text(help);
+            }
+        });
+    ui.end_row();
+}
+
+/// Picks the upstreams of a union remote, or of a combine remote when `combine` is set.
+fn render_upstreams_form(
+    ui: &mut Ui,
+    values: &mut BTreeMap<String, String>,
+    upstreams: &mut Vec<Upstream>,
+    storages: &[String],
+    combine: bool,
+) -> Vec<String> {
+    let mut errors = vec![];
+
+    if upstreams.is_empty() {
+        *upstreams = storages
+            .iter()
+            .map(|storage| Upstream {
+                remote: storage.clone(),
+                dir: storage.replace(' ', "_"),
+                ..Default::default()
+            })
+            .collect();
+    }
+
+    Grid::new("upstreams_form_grid")
+        .num_columns(3)
+        .spacing([8.0, 6.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label(RichText::new("Remote").strong());
+            ui.label(RichText::new("Folder").strong());
+            ui.label(RichText::new(if combine { "Shown as" } else { "Mode" }).strong());
+            ui.end_row();
+
+            for (i, upstream) in upstreams.iter_mut().enumerate() {
+                ui.checkbox(&mut upstream.selected, &upstream.remote);
+                ui.add_enabled(
+                    upstream.selected,
+                    TextEdit::singleline(&mut upstream.path)
+                        .hint_text("whole remote")
+                        .desired_width(160.0),
+                );
+                if combine {
+                    ui.add_enabled(
+                        upstream.selected,
+                        TextEdit::singleline(&mut upstream.dir).desired_width(120.0),
+                    );
+                } else {
+                    ui.add_enabled_ui(upstream.selected, |ui| {
+                        ComboBox::from_id_source(("union_form_mode", i))
+                            .selected_text(match upstream.mode.as_str() {
+                                "ro" => "Read only",
+                                "nc" => "No create",
+                                "writeback" => "Writeback",
+                                _ => "Read/write",
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut upstream.mode,
+                                    String::new(),
+                                    "Read/write",
+                                );
+                                ui.selectable_value(
+                                    &mut upstream.mode,
+                                    "ro".to_owned(),
+                                    "Read only",
+                                );
+                                ui.selectable_value(
+                                    &mut upstream.mode,
+                                    "nc".to_owned(),
+                                    "No create",
+                                )
+                                .on_hover_text("Existing files can change, new ones go elsewhere");
+                                ui.selectable_value(
+                                    &mut upstream.mode,
+                                    "writeback".to_owned(),
+                                    "Writeback",
+                                )
+                                .on_hover_text(
+                                    "Files read from the other upstreams are copied here",
+                                );
+                            });
+                    });
+                }
+                ui.end_row();
+            }
+        });
+
+    if !combine {
+        ui.add_space(4.0);
+        Grid::new("union_form_policies")
+            .num_columns(2)
+            .spacing([8.0, 6.0])
+            .show(ui, |ui| {
+                policy_row(
+                    ui,
+                    "Action policy",
+                    values.entry("action_policy".to_owned()).or_default(),
+                    "epall",
+                );
+                policy_row(
+                    ui,
+                    "Create policy",
+                    values.entry("create_policy".to_owned()).or_default(),
+                    "epmfs",
+                );
+                policy_row(
+                    ui,
+                    "Search policy",
+                    values.entry("search_policy".to_owned()).or_default(),
+                    "ff",
+                );
+            });
+    }
+
+    let selected: Vec<&Upstream> = upstreams.iter().filter(|u| u.selected).collect();
+    if selected.len() < 2 {
+        errors.push("Pick at least two remotes".to_owned());
+    }
+    if combine {
+        let mut dirs: Vec<&str> = selected.iter().map(|u| u.dir.trim()).collect();
+        if dirs.iter().any(|dir| dir.is_empty() || dir.contains('/')) {
+            errors.push("Every remote needs a folder name without slashes".to_owned());
+        }
+        dirs.sort_unstable();
+        dirs.dedup();
+        if dirs.len() != selected.len() {
+            errors.push("Folder names have to be unique".to_owned());
+        }
+    }
+
+    let value = selected
+        .iter()
+        .map(|upstream| upstream.to_value(combine))
+        .collect::<Vec<String>>()
+        .join(" ");
+    values.insert("upstreams".to_owned(), value);
+
+    errors
+}
+
+/// Lists the folders of `remote` for the alias form.
+fn list_folders(app: &mut DriveFUSE, ctx: &egui::Context, remote: String) {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return,
+    };
+    wizard.is_listing = true;
+    wizard.buckets = None;
+    wizard.test = None;
+
+    let name = wizard.name.clone();
+    let tx = app.tx_egui.clone();
+    let ctx = ctx.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = Rclone::list_dirs(&remote);
+        tx.send(Message::BucketsListed { name, result })
+            .expect("Failed to send BucketsListed message");
+        ctx.request_repaint();
+    });
+}
+
+/// Points an alias at a folder of an existing remote, browsed one level at a time.
+fn render_alias_form(ui: &mut Ui, app: &mut DriveFUSE, storages: &[String]) -> Vec<String> {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return vec![],
+    };
+    let mut errors = vec![];
+
+    let remote = wizard.values.get("remote").cloned().unwrap_or_default();
+    let (base, path) = match remote.split_once(':') {
+        Some((base, path)) => (base.to_owned(), path.to_owned()),
+        None => (String::new(), String::new()),
+    };
+    let mut new_remote: Option<String> = None;
+    // typing only changes the path, picking a remote or a folder lists it
+    let mut browse = false;
+
+    Grid::new("alias_form_grid")
+        .num_columns(2)
+        .spacing([8.0, 6.0])
+        .show(ui, |ui| {
+            ui.label("Remote *");
+            let mut selected = base.clone();
+            ComboBox::from_id_source("alias_form_remote")
+                .selected_text(if base.is_empty() {
+                    "Select remote"
+                } else {
+                    base.as_str()
+                })
+                .show_ui(ui, |ui| {
+                    for storage in storages {
+                        ui.selectable_value(&mut selected, storage.clone(), storage);
+                    }
+                });
+            if selected != base {
+                new_remote = Some(format!("{}:", selected));
+                browse = true;
+            }
+            ui.end_row();
+
+            ui.label("Folder");
+            ui.horizontal(|ui| {
+                let mut folder = path.clone();
+                ui.add_enabled(
+                    !base.is_empty(),
+                    TextEdit::singleline(&mut folder)
+                        .hint_text("whole remote")
+                        .desired_width(200.0),
+                );
+                if folder != path {
+                    new_remote = Some(format!("{}:{}", base, folder));
+                }
+
+                if ui
+                    .add_enabled(!path.is_empty() && !wizard.is_listing, Button::new("Up"))
+                    .clicked()
+                {
+                    let parent = match path.trim_end_matches('/').rsplit_once('/') {
+                        Some((parent, _)) => parent.to_owned(),
+                        None => String::new(),
+                    };
+                    new_remote = Some(format!("{}:{}", base, parent));
+                    browse = true;
+                }
+            });
+            ui.end_row();
+        });
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                !base.is_empty() && !wizard.is_listing,
+                Button::new("Browse"),
+            )
+            .clicked()
+        {
+            browse = true;
+        }
+        if wizard.is_listing {
+            ui.spinner();
+        }
+        if let Some(Err(err)) = &wizard.test {
+            ui.label(RichText::new(err).color(Color32::RED));
+        }
+    });
+
+    if let Some(folders) = &wizard.buckets {
+        ScrollArea::vertical()
+            .id_source("alias_form_folders")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                if folders.is_empty() {
+                    ui.label(RichText::new("No folders here").weak());
+                }
+                for folder in folders {
+                    if ui
+                        .add_enabled(!wizard.is_listing, Button::new(format!("📁 {}", folder)))
+                        .clicked()
+                    {
+                        let path = path.trim_end_matches('/');
+                        new_remote = Some(match path.is_empty() {
+                            true => format!("{}:{}", base, folder),
+                            false => format!("{}:{}/{}", base, path, folder),
+                        });
+                        browse = true;
+                    }
+                }
+            });
+    }
+
+    if base.is_empty() && new_remote.is_none() {
+        errors.push("Pick the remote to alias".to_owned());
+    }
+
+    let remote = match new_remote {
+        Some(new_remote) => {
+            wizard
+                .values
+                .insert("remote".to_owned(), new_remote.clone());
+            wizard.buckets = None;
+            new_remote
+        }
+        None => remote,
+    };
+    if browse {
+        list_folders(app, ui.ctx(), remote);
+    }
+
+    errors
+}
+
+/// Lets the user pick a file for one of the form's path options.
+fn pick_file(app: &DriveFUSE, ctx: &egui::Context, key: &str) {
+    let name = match &app.create_wizard {
+        Some(wizard) => wizard.name.clone(),
+        None => return,
+    };
+    let key = key.to_owned();
+    let tx = app.tx_egui.clone();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let res = rfd::AsyncFileDialog::new().pick_file().await;
+
+        if let Some(file) = res {
+            tx.send(Message::FormFilePicked {
+                name,
+                key,
+                path: file.path().to_path_buf(),
+            })
+            .expect("Failed to send FormFilePicked message");
+            ctx.request_repaint();
+        }
+    });
+}
+
+/// Buckets of the entered account, the picked one becomes the mount root.
+fn render_bucket_picker(ui: &mut Ui, app: &mut DriveFUSE, is_valid: bool) {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return,
+    };
+
+    ui.horizontal(|ui| {
+        ui.label("Mount root");
+        match &wizard.buckets {
+            Some(buckets) => {
+                let selected = wizard
+                    .mount_root
+                    .clone()
+                    .unwrap_or_else(|| "All buckets".to_owned());
+                ComboBox::from_id_source("s3_form_bucket")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut wizard.mount_root, None, "All buckets");
+                        for bucket in buckets {
+                            ui.selectable_value(
+                                &mut wizard.mount_root,
+                                Some(bucket.clone()),
+                                bucket,
+                            );
+                        }
+                    });
+            }
+            None => {
+                ui.label("All buckets");
+            }
+        }
+
+        if ui
+            .add_enabled(is_valid && !wizard.is_listing, Button::new("List buckets"))
+            .clicked()
+        {
+            wizard.is_listing = true;
+
+            let name = wizard.name.clone();
+            let backend = wizard.provider.prefix.clone();
+            let values = wizard.values.clone();
+            let passwords = wizard.passwords();
+            let tx = app.tx_egui.clone();
+            let ctx = ui.ctx().clone();
+            tokio::task::spawn_blocking(move || {
+                let result = prepare_options(&values, &passwords)
+                    .and_then(|options| Rclone::list_backend_dirs(&backend, &options, ""));
+                tx.send(Message::BucketsListed { name, result })
+                    .expect("Failed to send BucketsListed message");
+                ctx.request_repaint();
+            });
+        }
+        if wizard.is_listing {
+            ui.spinner();
+        }
+    });
+}
+
+/// Checks the entered options with `rclone lsd` before anything is saved.
+fn render_test_connection(ui: &mut Ui, app: &mut DriveFUSE, is_valid: bool) {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return,
+    };
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                is_valid && !wizard.is_testing,
+                Button::new("Test connection"),
+            )
+            .clicked()
+        {
+            wizard.is_testing = true;
+            wizard.test = None;
+
+            let name = wizard.name.clone();
+            let backend = wizard.provider.prefix.clone();
+            let values = wizard.values.clone();
+            let passwords = wizard.passwords();
+            let tx = app.tx_egui.clone();
+            let ctx = ui.ctx().clone();
+            tokio::task::spawn_blocking(move || {
+                let result = prepare_options(&values, &passwords)
+                    .and_then(|options| Rclone::test_backend(&backend, &options))
+                    .map(|elapsed| format!("Connected in {} ms", elapsed.as_millis()));
+                tx.send(Message::ConnectionTested { name, result })
+                    .expect("Failed to send ConnectionTested message");
+                ctx.request_repaint();
+            });
+        }
+
+        if wizard.is_testing {
+            ui.spinner();
+        }
+        match &wizard.test {
+            Some(Ok(message)) => {
+                ui.label(RichText::new(message).color(Color32::GREEN));
+            }
+            Some(Err(err)) => {
+                ui.label(RichText::new(err).color(Color32::RED));
+            }
+            None => {}
+        }
+    });
+}
+
+/// Renders the provider's own form, if it has one, returning its validation errors.
+pub fn render_remote_form(ui: &mut Ui, app: &mut DriveFUSE) -> Vec<String> {
+    let wizard = match &mut app.create_wizard {
+        Some(wizard) => wizard,
+        None => return vec![],
+    };
+    let storages: Vec<String> = app
+        .rclone
+        .storages
+        .iter()
+        .map(|s| s.name.clone())
+        .filter(|name| *name != wizard.name)
+        .collect();
+
+    let errors = match wizard.provider.prefix.as_str() {
+        "drive" => {
+            render_drive_create(ui, &mut wizard.values);
+            vec![]
+        }
+        "webdav" => {
+            let errors = render_webdav_form(ui, &mut wizard.values);
+            render_test_connection(ui, app, errors.is_empty());
+            errors
+        }
+        "s3" => {
+            let errors = render_s3_form(ui, &mut wizard.values);
+            render_bucket_picker(ui, app, errors.is_empty());
+            render_test_connection(ui, app, errors.is_empty());
+            errors
+        }
+        "sftp" => {
+            let mut browse = None;
+            let errors = render_sftp_form(ui, &mut wizard.values, &mut browse);
+            if let Some(key) = browse {
+                pick_file(app, ui.ctx(), key);
+            }
+            render_test_connection(ui, app, errors.is_empty());
+            errors
+        }
+        "ftp" => {
+            let errors = render_ftp_form(ui, &mut wizard.values);
+            render_test_connection(ui, app, errors.is_empty());
+            errors
+        }
+        "smb" => {
+            let errors = render_smb_form(ui, &mut wizard.values);
+            render_test_connection(ui, app, errors.is_empty());
+            errors
+        }
+        "crypt" => render_crypt_form(ui, &mut wizard.values, &storages),
+        "alias" => render_alias_form(ui, app, &storages),
+        "union" | "combine" => render_upstreams_form(
+            ui,
+            &mut wizard.values,
+            &mut wizard.upstreams,
+            &storages,
+            wizard.provider.prefix == "combine",
+        ),
+        _ => return vec![],
+    };
+
+    ui.separator();
+    errors
+}
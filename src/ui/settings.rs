@@ -2,10 +2,10 @@ use egui::{vec2, Button, CentralPanel, CollapsingHeader, Context, Rounding, Scro
 
 use crate::{
     utilities::{
-        enums::AppTheme,
+        enums::{AppTheme, Message},
         utils::{
             disable_auto_mount, disable_auto_start_app, enable_auto_mount, enable_auto_start_app,
-            is_app_auto_start,
+            is_app_auto_start, rclone_config_file,
         },
     },
     DriveFUSE,
@@ -98,6 +98,60 @@ pub fn render_settings(ctx: &Context, app: &mut DriveFUSE) {
                 CollapsingHeader::new("Config file")
                     .default_open(true)
                     .show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Active rclone config file:");
+                            ui.monospace(rclone_config_file().display().to_string());
+                            ui.label(format!("({})", app.config_file_source.name()));
+                        });
+
+                        ui.add_space(8.0);
+
+                        ui.horizontal(|ui| {
+                            ui.label("Storages loaded from:");
+                            ui.label(app.rclone.source.name());
+                        });
+
+                        ui.add_space(8.0);
+
+                        ui.horizontal(|ui| {
+                            ui.label("Use another rclone config file:");
+                            if ui.button("Choose").clicked() {
+                                let tx = app.tx_egui.clone();
+                                let ctx = ctx.clone();
+                                let directory = rclone_config_file()
+                                    .parent()
+                                    .map(|dir| dir.to_path_buf())
+                                    .unwrap_or_default();
+                                tokio::spawn(async move {
+                                    let res = rfd::AsyncFileDialog::new()
+                                        .add_filter("rclone config", &["conf"])
+                                        .set_directory(directory)
+                                        .pick_file()
+                                        .await;
+
+                                    if let Some(path) = res {
+                                        tx.send(Message::RcloneConfigFileSelected(
+                                            path.path().to_path_buf(),
+                                        ))
+                                        .expect("Failed to send RcloneConfigFileSelected message");
+                                        ctx.request_repaint();
+                                    }
+                                });
+                            }
+
+                            if ui
+                                .add_enabled(
+                                    app.app_config.rclone_config_file.is_some(),
+                                    Button::new("Auto detect"),
+                                )
+                                .clicked()
+                            {
+                                app.use_rclone_config_file(ctx, None);
+                            }
+                        });
+
+                        ui.add_space(8.0);
+
                         ui.horizontal(|ui| {
                             ui.label("Backup rclone config file:");
                             if ui.button("Backup").clicked() {
@@ -0,0 +1,37 @@
+use egui::{Align2, Button, Color32, Context, Key, RichText, TextEdit, Window};
+
+use crate::DriveFUSE;
+
+pub fn render_unlock_config(ctx: &Context, app: &mut DriveFUSE) {
+    Window::new("Unlock rclone config")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label("Your rclone config is encrypted.");
+            ui.label("Enter its password to list and mount your storages.");
+
+            ui.add_space(8.0);
+
+            let response = ui.add(
+                TextEdit::singleline(&mut app.config_password)
+                    .password(true)
+                    .hint_text("Config password"),
+            );
+
+            if let Some(error) = &app.config_password_error {
+                ui.add_space(4.0);
+                ui.label(RichText::new(error).color(Color32::RED));
+            }
+
+            ui.add_space(8.0);
+
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+            let unlock = ui.add_enabled(!app.config_password.is_empty(), Button::new("Unlock"));
+
+            if (unlock.clicked() || submitted) && !app.config_password.is_empty() {
+                let password = std::mem::take(&mut app.config_password);
+                app.config_password_error = app.rclone.unlock(password).err();
+            }
+        });
+}
@@ -1,5 +1,13 @@
+use std::{path::PathBuf, time::Duration};
+
 use serde::{Deserialize, Serialize};
 
+use crate::backend::{
+    bundle::{Bundle, BundleSettings},
+    rclone::SharedDrive,
+    remote_config::ConfigStep,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Tab {
     MountUnmount,
@@ -29,42 +37,375 @@ pub enum Message {
     MountAll,
     UnmountAll,
     MountedSuccess,
+    RcloneConfigFileSelected(PathBuf),
+    StorageOptionUpdated {
+        storage: String,
+        key: String,
+        result: Result<(), String>,
+    },
+    ReconnectFinished {
+        storage: String,
+        result: Result<(), String>,
+    },
+    StorageTested {
+        storage: String,
+        result: Result<Duration, String>,
+    },
+    BundleLoaded(Result<Bundle, String>),
+    SharedDrivesListed {
+        storage: String,
+        result: Result<Vec<SharedDrive>, String>,
+    },
+    BucketsListed {
+        name: String,
+        result: Result<Vec<String>, String>,
+    },
+    FormFilePicked {
+        name: String,
+        key: String,
+        path: PathBuf,
+    },
+    SecretsExported {
+        name: String,
+    },
+    ConnectionTested {
+        name: String,
+        result: Result<String, String>,
+    },
+    RemoteConfigStep {
+        name: String,
+        step: ConfigStep,
+    },
+    RemoteCreated {
+        name: String,
+    },
+    RemoteCreationFailed {
+        name: String,
+        error: String,
+    },
+    BundleImported {
+        imported: Vec<(String, Option<BundleSettings>)>,
+        errors: Vec<String>,
+    },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum StorageType {
-    GoogleDrive,
-    OneDrive,
-    Dropbox,
-    GooglePhotos,
-    // Mega,
-    NextCloud,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenState {
+    Valid,
+    Expiring,
+    Expired,
+    RefreshFailed,
+    Missing,
+}
+
+impl TokenState {
+    pub fn name(&self) -> &str {
+        match self {
+            TokenState::Valid => "Valid",
+            TokenState::Expiring => "Expiring",
+            TokenState::Expired => "Expired",
+            TokenState::RefreshFailed => "Refresh failed",
+            TokenState::Missing => "Not signed in",
+        }
+    }
+
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            TokenState::Valid => egui::Color32::GREEN,
+            TokenState::Expiring => egui::Color32::YELLOW,
+            TokenState::Expired => egui::Color32::from_rgb(255, 165, 0),
+            TokenState::RefreshFailed => egui::Color32::RED,
+            TokenState::Missing => egui::Color32::from_rgb(255, 165, 0),
+        }
+    }
+}
+
+/// What a failed storage test points at, read from rclone's error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFailure {
+    Auth,
+    NotFound,
+    Network,
+    Timeout,
+    Other,
+}
+
+impl TestFailure {
+    pub fn name(&self) -> &str {
+        match self {
+            TestFailure::Auth => "Sign in failed",
+            TestFailure::NotFound => "Not found",
+            TestFailure::Network => "Unreachable",
+            TestFailure::Timeout => "Timed out",
+            TestFailure::Other => "Failed",
+        }
+    }
+
+    pub fn from_error(error: &str) -> Self {
+        const AUTH: [&str; 14] = [
+            "401",
+            "403",
+            "unauthorized",
+            "unauthenticated",
+            "forbidden",
+            "access denied",
+            "permission denied",
+            "invalid_grant",
+            "invalid_client",
+            "invalid_token",
+            "token expired",
+            "expired or revoked",
+            "invalidaccesskeyid",
+            "signaturedoesnotmatch",
+        ];
+        const NOT_FOUND: [&str; 4] = [
+            "directory not found",
+            "nosuchbucket",
+            "not found",
+            "doesn't exist",
+        ];
+        const NETWORK: [&str; 9] = [
+            "no such host",
+            "connection refused",
+            "connection reset",
+            "network is unreachable",
+            "i/o timeout",
+            "dial tcp",
+            "tls handshake",
+            "certificate",
+            "unexpected eof",
+        ];
+
+        let error = error.to_lowercase();
+        let matches = |needles: &[&str]| needles.iter().any(|needle| error.contains(needle));
+        // our own timeout, rclone's network timeouts say "i/o timeout". Network errors go
+        // before auth ones since a failed token refresh names the token URL.
+        if error.starts_with("timed out after") {
+            TestFailure::Timeout
+        } else if matches(&NETWORK) {
+            TestFailure::Network
+        } else if matches(&AUTH) {
+            TestFailure::Auth
+        } else if matches(&NOT_FOUND) {
+            TestFailure::NotFound
+        } else {
+            TestFailure::Other
+        }
+    }
+}
+
+/// Access a Google Drive remote asks for when signing in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveScope {
+    Full,
+    ReadOnly,
+    File,
+    AppFolder,
 }
 
-impl StorageType {
+impl DriveScope {
     pub fn name(&self) -> &str {
         match self {
-            StorageType::GoogleDrive => "Google Drive",
-            StorageType::OneDrive => "OneDrive",
-            StorageType::Dropbox => "Dropbox",
-            StorageType::GooglePhotos => "Google Photos",
-            // StorageType::Mega => "Mega",
-            StorageType::NextCloud => "NextCloud",
+            DriveScope::Full => "Full access",
+            DriveScope::ReadOnly => "Read only",
+            DriveScope::File => "Files created by rclone",
+            DriveScope::AppFolder => "App folder",
         }
     }
 
-    pub fn values() -> [StorageType; 5] {
+    /// The value rclone stores in `scope`.
+    pub fn value(&self) -> &str {
+        match self {
+            DriveScope::Full => "drive",
+            DriveScope::ReadOnly => "drive.readonly",
+            DriveScope::File => "drive.file",
+            DriveScope::AppFolder => "drive.appfolder",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<Self> {
+        Self::values()
+            .into_iter()
+            .find(|scope| scope.value() == value.trim())
+    }
+
+    pub fn values() -> [DriveScope; 4] {
         [
-            StorageType::GoogleDrive,
-            StorageType::OneDrive,
-            StorageType::Dropbox,
-            StorageType::GooglePhotos,
-            // StorageType::Mega,
-            StorageType::NextCloud,
+            DriveScope::Full,
+            DriveScope::ReadOnly,
+            DriveScope::File,
+            DriveScope::AppFolder,
         ]
     }
 }
 
+/// Server software behind a WebDAV remote, which decides the quirks rclone works around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebdavVendor {
+    Nextcloud,
+    Owncloud,
+    Sharepoint,
+    Other,
+}
+
+impl WebdavVendor {
+    pub fn name(&self) -> &str {
+        match self {
+            WebdavVendor::Nextcloud => "Nextcloud",
+            WebdavVendor::Owncloud => "ownCloud",
+            WebdavVendor::Sharepoint => "SharePoint",
+            WebdavVendor::Other => "Other",
+        }
+    }
+
+    /// The value rclone stores in `vendor`.
+    pub fn value(&self) -> &str {
+        match self {
+            WebdavVendor::Nextcloud => "nextcloud",
+            WebdavVendor::Owncloud => "owncloud",
+            WebdavVendor::Sharepoint => "sharepoint",
+            WebdavVendor::Other => "other",
+        }
+    }
+
+    pub fn values() -> [WebdavVendor; 4] {
+        [
+            WebdavVendor::Nextcloud,
+            WebdavVendor::Owncloud,
+            WebdavVendor::Sharepoint,
+            WebdavVendor::Other,
+        ]
+    }
+}
+
+/// Common S3 compatible services, with the endpoint and region they usually need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum S3Preset {
+    Aws,
+    Minio,
+    Cloudflare,
+    Wasabi,
+    DigitalOcean,
+    Ceph,
+    Other,
+}
+
+impl S3Preset {
+    pub fn name(&self) -> &str {
+        match self {
+            S3Preset::Aws => "Amazon S3",
+            S3Preset::Minio => "MinIO",
+            S3Preset::Cloudflare => "Cloudflare R2",
+            S3Preset::Wasabi => "Wasabi",
+            S3Preset::DigitalOcean => "DigitalOcean Spaces",
+            S3Preset::Ceph => "Ceph",
+            S3Preset::Other => "Other",
+        }
+    }
+
+    /// The value rclone stores in `provider`.
+    pub fn value(&self) -> &str {
+        match self {
+            S3Preset::Aws => "AWS",
+            S3Preset::Minio => "Minio",
+            S3Preset::Cloudflare => "Cloudflare",
+            S3Preset::Wasabi => "Wasabi",
+            S3Preset::DigitalOcean => "DigitalOcean",
+            S3Preset::Ceph => "Ceph",
+            S3Preset::Other => "Other",
+        }
+    }
+
+    /// Example endpoint, `None` when rclone derives it from the region.
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            S3Preset::Aws => None,
+            S3Preset::Minio => Some("http://localhost:9000"),
+            S3Preset::Cloudflare => Some("https://ACCOUNT_ID.r2.cloudflarestorage.com"),
+            S3Preset::Wasabi => Some("s3.wasabisys.com"),
+            S3Preset::DigitalOcean => Some("nyc3.digitaloceanspaces.com"),
+            S3Preset::Ceph | S3Preset::Other => Some("https://s3.example.com"),
+        }
+    }
+
+    pub fn region(&self) -> &str {
+        match self {
+            S3Preset::Aws | S3Preset::Wasabi => "us-east-1",
+            S3Preset::Cloudflare => "auto",
+            _ => "",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<Self> {
+        Self::values()
+            .into_iter()
+            .find(|preset| preset.value() == value)
+    }
+
+    pub fn values() -> [S3Preset; 7] {
+        [
+            S3Preset::Aws,
+            S3Preset::Minio,
+            S3Preset::Cloudflare,
+            S3Preset::Wasabi,
+            S3Preset::DigitalOcean,
+            S3Preset::Ceph,
+            S3Preset::Other,
+        ]
+    }
+}
+
+/// How the storages were read from the rclone config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    RcloneDump,
+    File,
+}
+
+impl ConfigSource {
+    pub fn name(&self) -> &str {
+        match self {
+            ConfigSource::RcloneDump => "rclone config dump",
+            ConfigSource::File => "parsing the config file",
+        }
+    }
+}
+
+/// Where the active rclone config file was found.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFileSource {
+    Setting,
+    Environment,
+    Rclone,
+    Default,
+}
+
+impl ConfigFileSource {
+    pub fn name(&self) -> &str {
+        match self {
+            ConfigFileSource::Setting => "chosen in settings",
+            ConfigFileSource::Environment => "from RCLONE_CONFIG",
+            ConfigFileSource::Rclone => "reported by rclone",
+            ConfigFileSource::Default => "default location",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogSource {
+    Rclone,
+    Bundled,
+}
+
+impl CatalogSource {
+    pub fn name(&self) -> &str {
+        match self {
+            CatalogSource::Rclone => "installed rclone",
+            CatalogSource::Bundled => "bundled list",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AppTheme {
     #[serde(rename = "light")]
@@ -143,3 +484,36 @@ impl AppTheme {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::TestFailure;
+
+    #[test]
+    fn offline_token_refresh_is_a_network_failure() {
+        let error = r#"couldn't list directory: Get "https://oauth2.googleapis.com/token": dial tcp: lookup oauth2.googleapis.com: no such host"#;
+        assert_eq!(TestFailure::from_error(error), TestFailure::Network);
+    }
+
+    #[test]
+    fn classifies_rclone_errors() {
+        let cases = [
+            ("timed out after 20 seconds", TestFailure::Timeout),
+            ("read tcp 10.0.0.2:51234: i/o timeout", TestFailure::Network),
+            (
+                r#"oauth2: "invalid_grant" "Token has been expired or revoked.""#,
+                TestFailure::Auth,
+            ),
+            ("SignatureDoesNotMatch: status code: 403", TestFailure::Auth),
+            ("directory not found", TestFailure::NotFound),
+            (
+                "NoSuchBucket: The specified bucket does not exist",
+                TestFailure::NotFound,
+            ),
+            ("something else went wrong", TestFailure::Other),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(TestFailure::from_error(error), expected, "{}", error);
+        }
+    }
+}
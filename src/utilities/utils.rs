@@ -1,14 +1,21 @@
 use std::{
-    env,
-    path::PathBuf,
-    process::{Command, Stdio},
+    env, fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 use auto_launch::AutoLaunchBuilder;
 #[cfg(target_os = "windows")]
 use directories::{BaseDirs, UserDirs};
 
-use crate::DriveFUSE;
+use crate::{utilities::enums::ConfigFileSource, DriveFUSE};
 
 #[cfg(target_os = "windows")]
 use {std::os::windows::process::CommandExt, winapi::um::winbase, windows::Win32};
@@ -16,6 +23,192 @@ use {std::os::windows::process::CommandExt, winapi::um::winbase, windows::Win32}
 #[cfg(target_os = "linux")]
 use std::io::Cursor;
 
+/// Password of an encrypted rclone config, only ever kept in memory.
+static RCLONE_CONFIG_PASS: RwLock<Option<String>> = RwLock::new(None);
+
+/// The rclone config file every rclone invocation is pointed at with `--config`.
+static RCLONE_CONFIG_FILE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+pub fn set_rclone_config_pass(pass: Option<String>) {
+    *RCLONE_CONFIG_PASS
+        .write()
+        .expect("Unable to lock rclone config password") = pass;
+}
+
+pub fn has_rclone_config_pass() -> bool {
+    RCLONE_CONFIG_PASS
+        .read()
+        .expect("Unable to lock rclone config password")
+        .is_some()
+        || env::var_os("RCLONE_CONFIG_PASS").is_some()
+}
+
+/// Every rclone invocation goes through here so that child processes can read an
+/// encrypted config without ever prompting on stdin.
+pub fn rclone_command() -> Command {
+    let mut cmd = Command::new("rclone");
+    cmd.arg("--ask-password=false");
+
+    if let Some(config_file) = RCLONE_CONFIG_FILE
+        .read()
+        .expect("Unable to lock rclone config file")
+        .as_deref()
+    {
+        cmd.arg("--config").arg(config_file);
+    }
+
+    if let Some(pass) = RCLONE_CONFIG_PASS
+        .read()
+        .expect("Unable to lock rclone config password")
+        .as_deref()
+    {
+        cmd.env("RCLONE_CONFIG_PASS", pass);
+    }
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(winbase::CREATE_NO_WINDOW);
+
+    cmd
+}
+
+/// Runs the command like `Command::output`, killing it once `timeout` passes.
+pub fn output_with_timeout(cmd: &mut Command, timeout: Duration) -> Result<Output, String> {
+    output_cancellable(cmd, timeout, &AtomicBool::new(false))
+}
+
+/// Like `output_with_timeout`, but also kills the child as soon as `cancel` is set.
+pub fn output_cancellable(
+    cmd: &mut Command,
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> Result<Output, String> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| err.to_string())?;
+
+    // drain the pipes on their own threads so a chatty child never blocks on a full pipe
+    let stdout = drain_pipe(child.stdout.take());
+    let stderr = drain_pipe(child.stderr.take());
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|err| err.to_string())? {
+            Some(status) => break status,
+            None if cancel.load(Ordering::Relaxed) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("cancelled".to_owned());
+            }
+            None if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {} seconds", timeout.as_secs()));
+            }
+            None => thread::sleep(Duration::from_millis(50)),
+        }
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Writes a file holding secrets so only the user can read it, even while it is written.
+/// The content goes to a temporary file next to it that then replaces the file, so it is
+/// never left half written. On Windows the user's own folders are all the protection there is.
+pub fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    // replace the file a symlink points at rather than the link
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut tmp_path = path.clone().into_os_string();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let _ = fs::remove_file(&tmp_path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = options.open(&tmp_path).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(err) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+
+    fs::rename(&tmp_path, &path)
+}
+
+/// Checks a remote name against the rules rclone applies in `config create`, without
+/// trimming it first.
+pub fn validate_remote_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("The name cannot be empty".to_owned());
+    }
+    if name.starts_with(['-', ' ']) {
+        return Err("The name cannot start with - or a space".to_owned());
+    }
+    if name.ends_with(' ') {
+        return Err("The name cannot end with a space".to_owned());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_alphanumeric() && !"_-.+@ ".contains(*c))
+    {
+        return Err(format!(
+            "'{}' is not allowed, use letters, numbers, spaces and _ - . + @",
+            c
+        ));
+    }
+
+    Ok(())
+}
+
+/// Removes what `--vfs-cache-mode` left of a storage in rclone's default cache dir.
+pub fn remove_vfs_cache(name: &str) {
+    let cache_dir = match directories::BaseDirs::new() {
+        Some(base_dirs) => base_dirs.cache_dir().join("rclone"),
+        None => return,
+    };
+
+    for dir in ["vfs", "vfsMeta"] {
+        let path = cache_dir.join(dir).join(name);
+        if !path.exists() {
+            continue;
+        }
+        match fs::remove_dir_all(&path) {
+            Ok(()) => tracing::info!("Removed {}", path.display()),
+            Err(err) => tracing::error!("Error removing {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// The `remote:path` rclone mounts for a storage, `root` being a folder or bucket in it.
+pub fn remote_path(name: &str, root: Option<&String>) -> String {
+    let root = root.map(|root| root.trim_matches('/')).unwrap_or_default();
+    format!("{}:{}", name, root)
+}
+
+fn drain_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
 #[cfg(target_os = "linux")]
 pub fn open_drive_location(name: String) {
     let username = whoami::username();
@@ -57,14 +250,25 @@ pub fn check_if_mounted(_name: String) {
     }
 }
 
+#[cfg(target_family = "unix")]
+pub fn mount_directory(name: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "/{}/{}/drive_fuse/{}",
+        if cfg!(target_os = "linux") {
+            "home"
+        } else {
+            "Users"
+        },
+        whoami::username(),
+        name
+    ))
+}
+
 pub fn get_info(name: String) -> Result<String, String> {
-    let mut cmd = Command::new("rclone");
+    let mut cmd = rclone_command();
     cmd.args(["about", &format!("{}:", name), "--json"])
         .stdout(Stdio::piped());
 
-    #[cfg(target_os = "windows")]
-    cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
     let process = cmd.output();
     match process {
         Ok(result) => {
@@ -207,6 +411,64 @@ pub fn rclone_config_path() -> Option<PathBuf> {
     }
 }
 
+pub fn set_rclone_config_file(path: PathBuf) {
+    *RCLONE_CONFIG_FILE
+        .write()
+        .expect("Unable to lock rclone config file") = Some(path);
+}
+
+pub fn rclone_config_file() -> PathBuf {
+    match RCLONE_CONFIG_FILE
+        .read()
+        .expect("Unable to lock rclone config file")
+        .clone()
+    {
+        Some(path) => path,
+        None => rclone_config_path()
+            .expect("Unable to get rclone config path")
+            .join("rclone.conf"),
+    }
+}
+
+/// Picks the config file in the same order rclone would, with the user's choice first.
+pub fn detect_rclone_config_file(custom: Option<PathBuf>) -> (PathBuf, ConfigFileSource) {
+    if let Some(path) = custom {
+        return (path, ConfigFileSource::Setting);
+    }
+
+    if let Some(path) = env::var_os("RCLONE_CONFIG").filter(|path| !path.is_empty()) {
+        return (PathBuf::from(path), ConfigFileSource::Environment);
+    }
+
+    let mut cmd = Command::new("rclone");
+    cmd.args(["config", "file"]);
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(winbase::CREATE_NO_WINDOW);
+
+    match cmd.output() {
+        Ok(output) if output.status.success() => {
+            // the path is printed on the last line, after a human readable header
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Some(line) = stdout.lines().rev().find(|line| !line.trim().is_empty()) {
+                return (PathBuf::from(line.trim()), ConfigFileSource::Rclone);
+            }
+        }
+        Ok(output) => tracing::error!(
+            "rclone config file failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(err) => tracing::error!("Unable to run rclone config file: {}", err),
+    }
+
+    (
+        rclone_config_path()
+            .expect("Unable to get rclone config path")
+            .join("rclone.conf"),
+        ConfigFileSource::Default,
+    )
+}
+
 pub fn app_config_path() -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
     return UserDirs::new().map(|user_dirs| {
@@ -232,116 +494,42 @@ pub fn app_config_path() -> Option<PathBuf> {
     }
 }
 
-// Google Drive
-pub fn add_google_drive_storage(name: String) {
-    tokio::spawn(async move {
-        let mut cmd = Command::new("rclone");
-        let cmd = cmd.args(&[
-            String::from("config"),
-            String::from("create"),
-            name.trim().to_string(),
-            String::from("drive"),
-            String::from("config_is_local=true"),
-        ]);
-
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
-        cmd.spawn().expect("Unable to spawn command");
-    });
-}
-
-// OneDrive
-pub fn add_onedrive_storage(name: String) {
-    tokio::spawn(async move {
-        let mut cmd = Command::new("rclone");
-        let cmd = cmd.args(&[
-            String::from("config"),
-            String::from("create"),
-            name.trim().to_string(),
-            String::from("onedrive"),
-            String::from("config_is_local=true"),
-        ]);
-
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
-        cmd.spawn().expect("Unable to spawn command");
-    });
-}
-
-// Dropbox
-pub fn add_dropbox_storage(name: String) {
-    tokio::spawn(async move {
-        let mut cmd = Command::new("rclone");
-        let cmd = cmd.args(&[
-            String::from("config"),
-            String::from("create"),
-            name.trim().to_string(),
-            String::from("dropbox"),
-            String::from("config_is_local=true"),
-        ]);
-
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
-        cmd.spawn().expect("Unable to spawn command");
-    });
-}
-
-// Google Photos
-pub fn add_google_photos_storage(name: String) {
-    tokio::spawn(async move {
-        let mut cmd = Command::new("rclone");
-        let cmd = cmd.args(&[
-            String::from("config"),
-            String::from("create"),
-            name.trim().to_string(),
-            String::from("googlephotos"),
-            String::from("config_is_local=true"),
-        ]);
-
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
-        cmd.spawn().expect("Unable to spawn command");
-    });
-}
-
-// Mega
-pub fn add_mega_storage(name: String) {
-    tokio::spawn(async move {
-        let mut cmd = Command::new("rclone");
-        let cmd = cmd.args(&[
-            String::from("config"),
-            String::from("create"),
-            name.trim().to_string(),
-            String::from("mega"),
-            String::from("config_is_local=true"),
-        ]);
-
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
-        cmd.spawn().expect("Unable to spawn command");
-    });
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_names_rclone_accepts() {
+        for name in [
+            "gdrive",
+            "my drive",
+            "work.s3+backup@2",
+            "a-b_c",
+            "Übung",
+            "文档",
+            "x-",
+        ] {
+            assert_eq!(validate_remote_name(name), Ok(()), "{}", name);
+        }
+    }
 
-// NextCloud
-pub fn add_nextcloud_storage(name: String) {
-    tokio::spawn(async move {
-        let mut cmd = Command::new("rclone");
-        let cmd = cmd.args(&[
-            String::from("config"),
-            String::from("create"),
-            name.trim().to_string(),
-            String::from("nextcloud"),
-            String::from("config_is_local=true"),
-        ]);
-
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(winbase::CREATE_NO_WINDOW);
-
-        cmd.spawn().expect("Unable to spawn command");
-    });
+    #[test]
+    fn rejects_names_rclone_rejects() {
+        for name in [
+            "",
+            "-drive",
+            " drive",
+            "drive ",
+            "my:drive",
+            "a/b",
+            "a\\b",
+            "tab\tname",
+        ] {
+            assert!(validate_remote_name(name).is_err(), "{:?}", name);
+        }
+        assert_eq!(
+            validate_remote_name("my:drive"),
+            Err("':' is not allowed, use letters, numbers, spaces and _ - . + @".to_owned())
+        );
+    }
 }